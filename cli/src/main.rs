@@ -9,6 +9,17 @@ const VALID_STATES: &[&str] = &[
     "unknown", "idle", "working", "question", "sleeping", "watching", "attention",
 ];
 
+/// Built-in states plus the plugin's `custom_states`, which the CLI can't read from the
+/// zellij config: mirror them in $ZELLIJ_CREW_STATES (space-separated).
+fn valid_states() -> Vec<String> {
+    let custom = env::var("ZELLIJ_CREW_STATES").unwrap_or_default();
+    VALID_STATES
+        .iter()
+        .map(|s| s.to_string())
+        .chain(custom.split_whitespace().map(|s| s.to_string()))
+        .collect()
+}
+
 struct HookMapping {
     event: &'static str,
     state: &'static str,
@@ -36,14 +47,24 @@ fn print_help() {
     eprintln!();
     eprintln!("Usage:");
     eprintln!("  zellij-crew status <state>          Send status update to plugin");
+    eprintln!("      [--ttl SECS [--revert STATE]]   Revert after SECS (default: to the previous state)");
+    eprintln!("      [--note TEXT]                   Short note shown in the bar and state");
+    eprintln!("  zellij-crew progress <0-100>        Report job progress (marks the tab working)");
+    eprintln!("  zellij-crew heartbeat               Tell the plugin this tab's agent is alive");
+    eprintln!("  zellij-crew key [KEY]               Report the hash mode key (default: project dir)");
     eprintln!("  zellij-crew tell <name> <message>   Send message to another tab");
     eprintln!("  zellij-crew state                   Show detailed per-tab state (JSON)");
+    eprintln!("  zellij-crew history <name> [--json] Show recent status changes of a tab");
+    eprintln!("  zellij-crew rename <name>           Rename this tab (marks it user-named)");
+    eprintln!("  zellij-crew release                 Return this tab to pool naming");
+    eprintln!("  zellij-crew claim <name>            Take a specific pool or reserved name");
+    eprintln!("  zellij-crew reload-names            Re-read the plugin's names_file");
     eprintln!("  zellij-crew --setup                 Install hooks into ~/.claude/settings.json");
     eprintln!("  zellij-crew --remove                Remove hooks from ~/.claude/settings.json");
     eprintln!("  zellij-crew --help                  Show this help");
     eprintln!();
-    eprintln!("Valid states:");
-    for s in VALID_STATES {
+    eprintln!("Valid states (custom states from $ZELLIJ_CREW_STATES):");
+    for s in valid_states() {
         eprintln!("  {}", s);
     }
     eprintln!();
@@ -222,37 +243,183 @@ fn require_zellij() -> String {
     }
 }
 
+/// Hand over to `zellij pipe`. The payload is always explicit: without one, zellij would
+/// read it from stdin, which hooks fill with their own JSON.
+fn exec_pipe(name: &str, pipe_args: &str, payload: &str) -> ! {
+    let err = process::Command::new("zellij")
+        .args(["pipe", "--name", name, "--args", pipe_args, "--", payload])
+        .exec();
+    eprintln!("zellij-crew: failed to exec zellij: {}", err);
+    process::exit(1);
+}
+
+/// Allocation key for the plugin's hash mode: $ZELLIJ_CREW_KEY, else the project directory
+/// (nearest ancestor with a .git entry, or the current directory). ',' and '=' would break
+/// the pipe args format, so they are replaced.
+fn project_key() -> Option<String> {
+    let key = match env::var("ZELLIJ_CREW_KEY") {
+        Ok(key) if !key.is_empty() => key,
+        _ => {
+            let cwd = env::current_dir().ok()?;
+            let root = cwd
+                .ancestors()
+                .find(|dir| dir.join(".git").exists())
+                .unwrap_or(&cwd);
+            root.display().to_string()
+        }
+    };
+    Some(key.replace([',', '='], "_"))
+}
+
+fn status_usage() -> ! {
+    eprintln!("Usage: zellij-crew status <state> [--ttl SECS [--revert STATE]] [--note TEXT]");
+    process::exit(1);
+}
+
 fn do_status(args: &[String]) {
     if args.is_empty() {
-        eprintln!("Usage: zellij-crew status <state>");
-        eprintln!("Valid states: {}", VALID_STATES.join(" "));
-        process::exit(1);
+        status_usage();
     }
 
     let pane_id = require_zellij();
     let state = args[0].as_str();
 
-    if !VALID_STATES.contains(&state) {
+    let valid = valid_states();
+    if !valid.iter().any(|s| s == state) {
         eprintln!("zellij-crew: invalid state '{}'", state);
-        eprintln!("Valid states: {}", VALID_STATES.join(" "));
+        eprintln!("Valid states: {}", valid.join(" "));
         process::exit(1);
     }
 
-    let pipe_args = format!("pane={},state={}", pane_id, state);
-    let err = process::Command::new("zellij")
-        .args(["pipe", "--name", "zellij-crew:status", "--args", &pipe_args])
-        .exec();
-    eprintln!("zellij-crew: failed to exec zellij: {}", err);
-    process::exit(1);
+    let mut pipe_args = format!("pane={},state={}", pane_id, state);
+    let mut rest = args[1..].iter();
+    while let Some(flag) = rest.next() {
+        let value = rest.next().unwrap_or_else(|| status_usage());
+        match flag.as_str() {
+            "--ttl" if value.parse::<u64>().is_ok_and(|secs| secs > 0) => {
+                pipe_args.push_str(&format!(",ttl={}", value));
+            }
+            "--revert" if valid.iter().any(|s| s == value) => {
+                pipe_args.push_str(&format!(",revert={}", value));
+            }
+            // ',' and '=' would split the pipe args; --note "" clears the note
+            "--note" => {
+                pipe_args.push_str(&format!(",note={}", value.replace(',', ";").replace('=', ":")));
+            }
+            _ => {
+                eprintln!("zellij-crew: invalid option {} {}", flag, value);
+                status_usage();
+            }
+        }
+    }
+    exec_pipe("zellij-crew:status", &pipe_args, "");
+}
+
+fn do_key(args: &[String]) {
+    let key = match args {
+        [] => project_key(),
+        [key] => Some(key.replace([',', '='], "_")),
+        _ => {
+            eprintln!("Usage: zellij-crew key [KEY]");
+            process::exit(1);
+        }
+    };
+
+    let pane_id = require_zellij();
+    let key = key.unwrap_or_else(|| {
+        eprintln!("zellij-crew: no key (current directory unavailable)");
+        process::exit(1);
+    });
+    let pipe_args = format!("pane={},key={}", pane_id, key);
+    exec_pipe("zellij-crew:status", &pipe_args, "");
+}
+
+fn do_progress(args: &[String]) {
+    let pct = match args.first().map(|s| s.trim_end_matches('%').parse::<u8>()) {
+        Some(Ok(pct)) if pct <= 100 && args.len() == 1 => pct,
+        _ => {
+            eprintln!("Usage: zellij-crew progress <0-100>");
+            process::exit(1);
+        }
+    };
+
+    let pane_id = require_zellij();
+    let pipe_args = format!("pane={},progress={}", pane_id, pct);
+    exec_pipe("zellij-crew:status", &pipe_args, "");
+}
+
+fn do_heartbeat() {
+    let pane_id = require_zellij();
+    let pipe_args = format!("pane={},heartbeat", pane_id);
+    exec_pipe("zellij-crew:status", &pipe_args, "");
 }
 
 fn do_state() {
     require_zellij();
-    let err = process::Command::new("zellij")
-        .args(["pipe", "--name", "zellij-crew:status", "--args", "format=json,state_query", "--", ""])
-        .exec();
-    eprintln!("zellij-crew: failed to exec zellij: {}", err);
-    process::exit(1);
+    exec_pipe("zellij-crew:status", "format=json,state_query", "");
+}
+
+fn do_history(args: &[String]) {
+    let (name, json) = match args {
+        [name] => (name, false),
+        [name, flag] | [flag, name] if flag == "--json" => (name, true),
+        _ => {
+            eprintln!("Usage: zellij-crew history <name> [--json]");
+            process::exit(1);
+        }
+    };
+
+    require_zellij();
+    let mut pipe_args = format!("history={}", name);
+    if json {
+        pipe_args.push_str(",format=json");
+    }
+    exec_pipe("zellij-crew:status", &pipe_args, "");
+}
+
+fn do_rename(args: &[String]) {
+    if args.is_empty() {
+        eprintln!("Usage: zellij-crew rename <name>");
+        process::exit(1);
+    }
+
+    let pane_id = require_zellij();
+    let name = args.join(" ");
+    if name.contains(',') || name.contains('=') {
+        eprintln!("zellij-crew: name must not contain ',' or '='");
+        process::exit(1);
+    }
+
+    let pipe_args = format!("pane={},rename={}", pane_id, name);
+    exec_pipe("zellij-crew:status", &pipe_args, "");
+}
+
+fn do_claim(args: &[String]) {
+    if args.len() != 1 {
+        eprintln!("Usage: zellij-crew claim <name>");
+        process::exit(1);
+    }
+
+    let pane_id = require_zellij();
+    let name = &args[0];
+    if name.contains(',') || name.contains('=') {
+        eprintln!("zellij-crew: name must not contain ',' or '='");
+        process::exit(1);
+    }
+
+    let pipe_args = format!("pane={},claim={}", pane_id, name);
+    exec_pipe("zellij-crew:status", &pipe_args, "");
+}
+
+fn do_release() {
+    let pane_id = require_zellij();
+    let pipe_args = format!("pane={},release", pane_id);
+    exec_pipe("zellij-crew:status", &pipe_args, "");
+}
+
+fn do_reload_names() {
+    require_zellij();
+    exec_pipe("zellij-crew:status", "reload-names", "");
 }
 
 fn do_tell(args: &[String]) {
@@ -266,15 +433,7 @@ fn do_tell(args: &[String]) {
     let message = args[1..].join(" ");
 
     let pipe_args = format!("to={},pane={}", dest, pane_id);
-    let err = process::Command::new("zellij")
-        .args([
-            "pipe", "--name", "zellij-crew:msg",
-            "--args", &pipe_args,
-            "--", &message,
-        ])
-        .exec();
-    eprintln!("zellij-crew: failed to exec zellij: {}", err);
-    process::exit(1);
+    exec_pipe("zellij-crew:msg", &pipe_args, &message);
 }
 
 fn main() {
@@ -290,8 +449,16 @@ fn main() {
         "--setup" => do_setup(),
         "--remove" => do_remove(),
         "status" => do_status(&args[1..]),
+        "progress" => do_progress(&args[1..]),
+        "heartbeat" => do_heartbeat(),
+        "key" => do_key(&args[1..]),
         "state" => do_state(),
+        "history" => do_history(&args[1..]),
         "tell" => do_tell(&args[1..]),
+        "rename" => do_rename(&args[1..]),
+        "release" => do_release(),
+        "claim" => do_claim(&args[1..]),
+        "reload-names" => do_reload_names(),
         other => {
             eprintln!("zellij-crew: unknown command '{}'", other);
             eprintln!("Run with --help for usage");
@@ -10,8 +10,18 @@ fn get_current_title_len(current_title: &[LinePart]) -> usize {
     current_title.iter().map(|p| p.len).sum()
 }
 
+// 1-based position range of collapsed tabs ("3" or "1-4"), matching the show_position labels
+fn position_range(first_tab_index: usize, count: usize) -> String {
+    if count == 1 {
+        (first_tab_index + 1).to_string()
+    } else {
+        format!("{}-{}", first_tab_index + 1, first_tab_index + count)
+    }
+}
+
 // move elements from before_active and after_active into tabs_to_render while they fit in cols
 // adds collapsed_tabs to the left and right if there's left over tabs that don't fit
+// tab lengths already include any show_position label, so labelled tabs collapse sooner
 fn populate_tabs_in_tab_line(
     tabs_before_active: &mut Vec<LinePart>,
     tabs_after_active: &mut Vec<LinePart>,
@@ -19,6 +29,7 @@ fn populate_tabs_in_tab_line(
     cols: usize,
     palette: Styling,
     capabilities: PluginCapabilities,
+    show_positions: bool,
 ) {
     let mut middle_size = get_current_title_len(tabs_to_render);
 
@@ -35,6 +46,7 @@ fn populate_tabs_in_tab_line(
             palette,
             tab_separator(capabilities),
             left_more_tab_index,
+            show_positions,
         );
 
         // right_more_tab_index is the first tab to the right of the rightmost visible tab
@@ -44,6 +56,7 @@ fn populate_tabs_in_tab_line(
             palette,
             tab_separator(capabilities),
             right_more_tab_index,
+            show_positions,
         );
 
         let total_size = collapsed_left.len + middle_size + collapsed_right.len;
@@ -112,11 +125,15 @@ fn left_more_message(
     palette: Styling,
     separator: &str,
     tab_index: usize,
+    show_positions: bool,
 ) -> LinePart {
     if tab_count_to_the_left == 0 {
         return LinePart::default();
     }
-    let more_text = if tab_count_to_the_left < 10000 {
+    // with positions shown, name the hidden Alt+N targets instead of counting them
+    let more_text = if show_positions {
+        format!(" ← {} ", position_range(0, tab_count_to_the_left))
+    } else if tab_count_to_the_left < 10000 {
         format!(" ← +{} ", tab_count_to_the_left)
     } else {
         " ← +many ".to_string()
@@ -147,11 +164,14 @@ fn right_more_message(
     palette: Styling,
     separator: &str,
     tab_index: usize,
+    show_positions: bool,
 ) -> LinePart {
     if tab_count_to_the_right == 0 {
         return LinePart::default();
     };
-    let more_text = if tab_count_to_the_right < 10000 {
+    let more_text = if show_positions {
+        format!(" {} → ", position_range(tab_index, tab_count_to_the_right))
+    } else if tab_count_to_the_right < 10000 {
         format!(" +{} → ", tab_count_to_the_right)
     } else {
         " +many → ".to_string()
@@ -204,6 +224,36 @@ fn tab_line_prefix(session_name: Option<&str>, palette: Styling, cols: usize) ->
     parts
 }
 
+// Width tab_line reserves for the prefix and swap layout indicator (what the tabs can't use)
+pub fn non_tab_len(
+    session_name: Option<&str>,
+    cols: usize,
+    palette: Styling,
+    capabilities: PluginCapabilities,
+    hide_session_name: bool,
+    tab_info: Option<&TabInfo>,
+    mode_info: &ModeInfo,
+    hide_swap_layout_indicator: bool,
+) -> usize {
+    let prefix = tab_line_prefix(if hide_session_name { None } else { session_name }, palette, cols);
+    let swap_len = if hide_swap_layout_indicator {
+        0
+    } else {
+        tab_info
+            .and_then(|tab_info| {
+                swap_layout_status(
+                    &tab_info.active_swap_layout_name,
+                    tab_info.is_swap_layout_dirty,
+                    mode_info,
+                    !capabilities.arrow_fonts,
+                )
+            })
+            .map(|s| s.len)
+            .unwrap_or(0)
+    };
+    get_current_title_len(&prefix) + swap_len
+}
+
 pub fn tab_separator(capabilities: PluginCapabilities) -> &'static str {
     if !capabilities.arrow_fonts {
         ARROW_SEPARATOR
@@ -223,6 +273,7 @@ pub fn tab_line(
     tab_info: Option<&TabInfo>,
     mode_info: &ModeInfo,
     hide_swap_layout_indicator: bool,
+    show_positions: bool,
     background: &PaletteColor,
 ) -> Vec<LinePart> {
     let mut tabs_after_active = all_tabs.split_off(active_tab_index);
@@ -262,6 +313,7 @@ pub fn tab_line(
         cols.saturating_sub(non_tab_len),
         palette,
         capabilities,
+        show_positions,
     );
     prefix.append(&mut tabs_to_render);
     prefix.append(&mut vec![LinePart {
@@ -462,3 +514,27 @@ pub fn action_key(
         })
         .collect::<Vec<KeyWithModifier>>()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn position_range_is_one_based() {
+        assert_eq!(position_range(0, 1), "1");
+        assert_eq!(position_range(0, 4), "1-4");
+        assert_eq!(position_range(5, 3), "6-8");
+    }
+
+    #[test]
+    fn more_messages_count_label_width() {
+        let separator = ARROW_SEPARATOR;
+        let left = left_more_message(4, Styling::default(), separator, 0, true);
+        assert_eq!(left.len, " ← 1-4 ".width() + 2 * separator.width());
+        let right = right_more_message(3, Styling::default(), separator, 5, true);
+        assert_eq!(right.len, " 6-8 → ".width() + 2 * separator.width());
+        let counted = right_more_message(3, Styling::default(), separator, 5, false);
+        assert_eq!(counted.len, " +3 → ".width() + 2 * separator.width());
+        assert_eq!(left_more_message(0, Styling::default(), separator, 0, true).len, 0);
+    }
+}
@@ -5,17 +5,18 @@ mod line;
 mod tab;
 
 use std::cmp::{max, min};
-use std::collections::{BTreeMap, HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
 use std::convert::TryInto;
-use std::fs::OpenOptions;
+use std::fs::{self, OpenOptions};
 use std::io::Write as IoWrite;
 use std::time::SystemTime;
 
+use regex::{Regex, RegexBuilder};
 use serde::{Deserialize, Serialize};
 use tab::get_tab_to_focus;
 use zellij_tile::prelude::*;
 
-use crate::line::tab_line;
+use crate::line::{non_tab_len, tab_line};
 use crate::tab::tab_style;
 
 // ============================================================================
@@ -42,7 +43,29 @@ pub static ARROW_SEPARATOR: &str = "\u{e0b0}";
 // Configuration
 // ============================================================================
 
-const DEFAULT_NAMES: &str = "alpha bravo charlie delta echo foxtrot golf hotel india juliet kilo lima mike november oscar papa quebec romeo sierra tango uniform victor whiskey xray yankee zulu";
+const POOL_NATO: &str = "alpha bravo charlie delta echo foxtrot golf hotel india juliet kilo lima mike november oscar papa quebec romeo sierra tango uniform victor whiskey xray yankee zulu";
+const POOL_GREEK: &str = "alpha beta gamma delta epsilon zeta eta theta iota kappa lambda mu nu xi omicron pi rho sigma tau upsilon phi chi psi omega";
+const POOL_STARWARS: &str = "luke leia han chewie vader yoda obiwan palpatine r2d2 c3po boba lando mace windu anakin padme";
+
+const DEFAULT_NAMES: &str = POOL_NATO;
+
+/// Built-in name pools selectable with the `pool` option (e.g. `pool "nato+greek"`).
+const BUILTIN_POOLS: &[(&str, &str)] = &[
+    ("nato", POOL_NATO),
+    ("greek", POOL_GREEK),
+    ("starwars", POOL_STARWARS),
+];
+
+// Word lists for `on_exhausted "generate"`. Lengths are coprime so stepping one index
+// through both lists visits every adjective-noun pair before repeating.
+const GENERATED_ADJECTIVES: &[&str] = &[
+    "amber", "bold", "brave", "calm", "clever", "eager", "fuzzy", "gentle",
+    "happy", "jolly", "lucky", "mellow", "nimble", "quiet", "rapid", "sunny",
+];
+const GENERATED_NOUNS: &[&str] = &[
+    "badger", "beaver", "cobra", "crane", "falcon", "ferret", "gecko", "heron", "koala",
+    "lemur", "lynx", "marmot", "otter", "panda", "raven", "tapir", "walrus",
+];
 
 // Election protocol: all tab-bar instances elect a leader among themselves.
 // Tiebreaker: highest plugin_id wins (newer instances get higher IDs).
@@ -52,10 +75,18 @@ const MSG_LEADER_ACK: &str = "crew-leader-ack";
 const MSG_LEADER_CLAIM: &str = "crew-leader-claim";
 const MSG_LEADER_RESIGN: &str = "crew-leader-resign";
 
+// Leader state is persisted to the plugin data dir (WASI /data, shared by all instances and
+// kept across sessions) so a resurrected session can restore it by tab name.
+const STATE_DIR: &str = "/data";
+
 #[derive(Debug, Clone, PartialEq)]
 enum AllocationMode {
     RoundRobin,
     FillIn,
+    /// Uniformly random free name; `seed` makes the sequence reproducible
+    Random,
+    /// Name picked by hashing a key reported by the CLI (e.g. project dir); fill-in until then
+    Hash,
 }
 
 impl Default for AllocationMode {
@@ -64,59 +95,379 @@ impl Default for AllocationMode {
     }
 }
 
+/// Where new tab names come from.
+#[derive(Debug, Clone, PartialEq, Default)]
+enum NamingMode {
+    /// Names from the pool
+    #[default]
+    Pool,
+    /// Names derived from the tab's terminal command or title, pool as fallback
+    Command,
+}
+
+/// What to do when every pool name is taken.
+#[derive(Debug, Clone, PartialEq, Default)]
+enum ExhaustedStrategy {
+    /// Leave the tab with its default "Tab #N" name
+    #[default]
+    None,
+    /// Lowest free numeric suffix on a pool name: "alpha-2", "bravo-2", ..., "alpha-3"
+    Suffix,
+    /// Keep cycling the pool with a generation counter that only moves forward
+    Cycle,
+    /// Adjective-noun names: "brave-otter"
+    Generate,
+}
+
+/// What the leader does when two tabs end up with the same name.
+#[derive(Debug, Clone, PartialEq, Default)]
+enum DuplicateResolution {
+    /// Keep both names, mark the tabs with ⚠ in the bar
+    #[default]
+    Warn,
+    /// Give the pool-assigned tab a fresh name (the user's choice wins)
+    RenamePool,
+    /// Add a numeric suffix to the tab that took the name last: "alpha-2"
+    SuffixNewcomer,
+}
+
+/// How `show_position` renders a tab's 1-based position (the Alt+N target).
+#[derive(Debug, Clone, PartialEq)]
+enum PositionFormat {
+    /// "alpha <1>"
+    Angle,
+    /// "1:alpha"
+    Colon,
+    /// "alpha¹"
+    Superscript,
+}
+
+impl PositionFormat {
+    fn label(&self, name: &str, position: usize) -> String {
+        let n = position + 1;
+        match self {
+            Self::Angle => format!("{} <{}>", name, n),
+            Self::Colon => format!("{}:{}", n, name),
+            Self::Superscript => {
+                const DIGITS: [char; 10] = ['⁰', '¹', '²', '³', '⁴', '⁵', '⁶', '⁷', '⁸', '⁹'];
+                let sup: String = n
+                    .to_string()
+                    .chars()
+                    .filter_map(|c| c.to_digit(10).map(|d| DIGITS[d as usize]))
+                    .collect();
+                format!("{}{}", name, sup)
+            }
+        }
+    }
+}
+
+/// How a working tab's progress (progress=0..100) is shown next to its indicator.
+#[derive(Debug, Clone, PartialEq, Default)]
+enum ProgressStyle {
+    /// "[🤖 42%]"
+    #[default]
+    Percent,
+    /// "[🤖 ▰▰▱▱▱]"
+    Bar,
+}
+
+impl ProgressStyle {
+    fn format(&self, pct: u8) -> String {
+        match self {
+            Self::Percent => format!("{}%", pct),
+            Self::Bar => {
+                const CELLS: usize = 5;
+                let filled = (pct as usize * CELLS + 50) / 100;
+                format!("{}{}", "▰".repeat(filled), "▱".repeat(CELLS - filled))
+            }
+        }
+    }
+}
+
+/// What happens to an attention/question tab once the user looks at it (`ack_on_focus`).
+#[derive(Debug, Clone, PartialEq, Default)]
+enum AckOnFocus {
+    /// Keep the indicator until a status update changes it
+    #[default]
+    Never,
+    /// Clear to idle as soon as the tab becomes active
+    Immediate,
+    /// Clear once the tab has been active for this many seconds
+    After(u64),
+}
+
+/// Which allocation path produced a tab's name (reported by the state query).
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+enum NameSource {
+    #[default]
+    Pool,
+    Suffix,
+    Cycle,
+    Generated,
+    Command,
+    Claimed,
+    User,
+}
+
+impl NameSource {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pool => "pool",
+            Self::Suffix => "suffix",
+            Self::Cycle => "cycle",
+            Self::Generated => "generated",
+            Self::Command => "command",
+            Self::Claimed => "claimed",
+            Self::User => "user",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Default)]
 struct Config {
     names: Vec<String>,
+    /// File the pool was loaded from (re-read by the reload-names command).
+    names_file: Option<String>,
+    /// Names only handed out by an explicit claim, never by automatic allocation.
+    reserved: Vec<String>,
+    /// Pattern applied to pool names, e.g. "{session}-{pool}" (None = plain pool names).
+    name_template: Option<String>,
     mode: AllocationMode,
-    // TODO: Implement show_position feature (see DESIGN.md Future Enhancements)
-    // show_position: bool,  // Would show "alpha <1>" style names
+    /// Seed for random mode (None = seeded from the clock).
+    seed: Option<u64>,
+    naming: NamingMode,
+    on_exhausted: ExhaustedStrategy,
+    on_duplicate: DuplicateResolution,
+    /// Show each tab's position next to its name (None = off).
+    show_position: Option<PositionFormat>,
+    progress_style: ProgressStyle,
     hide_swap_layout_indication: bool,
     /// Per-status indicator overrides. Key present with empty string = suppress brackets entirely.
     /// Key absent = use default emoji.
     status_indicators: HashMap<ActivityStatus, String>,
+    /// States declared with `custom_states`, in config order.
+    custom_states: Vec<ActivityStatus>,
+    /// Per-status `sleep_<state>` overrides (absent = ActivityStatus::default_sleeps).
+    sleep_eligible: HashMap<ActivityStatus, bool>,
+    /// Per-status `priority_<state>` overrides (absent = ActivityStatus::default_priority).
+    priorities: HashMap<ActivityStatus, i32>,
+    /// Per-status `wake_<state>` overrides: what a tab that fell asleep in that state wakes to
+    /// (absent = the state itself).
+    wake_rules: HashMap<ActivityStatus, ActivityStatus>,
+    /// `detect_<state>` regexes matched against the bottom of a pane's viewport.
+    detect_rules: Vec<(ActivityStatus, Regex)>,
+    /// Non-blank viewport lines (from the bottom) the detect rules see.
+    detect_lines: usize,
     /// Appended to tell messages. Substitutions: {from}, {to}, {message}, {id}.
     tell_append: String,
     /// Delay in ms between message text and Enter keystroke.
     tell_delay_ms: u32,
     /// Seconds of no terminal output before an idle tab transitions to sleeping (0 = disabled).
     idle_sleep_secs: u64,
+    /// Seconds of no terminal output before a working tab counts as hung (0 = disabled).
+    stalled_after_secs: u64,
+    /// Status a hung working tab moves to (`stalled_status`, default attention).
+    stalled_status: ActivityStatus,
+    /// Seconds without a heartbeat before a heartbeating tab counts as lost (0 = disabled).
+    heartbeat_timeout_secs: u64,
+    /// Marker shown next to a lost tab's name ("" = none).
+    lost_indicator: String,
+    /// Status reported for a terminal pane that exits with code 0 (None = ignore).
+    on_exit_ok: Option<ActivityStatus>,
+    /// Status reported for a terminal pane that exits with any other code (None = ignore).
+    on_exit_error: Option<ActivityStatus>,
+    /// Seconds a closed tab's name is held back from allocation (0 = reuse immediately).
+    name_quarantine_secs: u64,
+    ack_on_focus: AckOnFocus,
+}
+
+/// Expand a `+`-separated list of built-in pool keywords into a name list.
+/// Names shared between pools (e.g. "alpha" in nato and greek) appear once, in first-seen order.
+fn names_from_pools(spec: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for key in spec.split('+').map(|k| k.trim()).filter(|k| !k.is_empty()) {
+        match BUILTIN_POOLS.iter().find(|(name, _)| name.eq_ignore_ascii_case(key)) {
+            Some((_, pool)) => {
+                for name in pool.split_whitespace() {
+                    if !names.iter().any(|n| n == name) {
+                        names.push(name.to_string());
+                    }
+                }
+            }
+            None => eprintln!("[crew] Unknown pool '{}' (available: {})", key,
+                BUILTIN_POOLS.iter().map(|(name, _)| *name).collect::<Vec<_>>().join(", ")),
+        }
+    }
+    names
+}
+
+/// Read a `names_file`: one name per line, blank lines and `#` comments ignored.
+/// The path is as the plugin sees it (zellij maps its working directory to /host).
+fn read_names_file(path: &str) -> Result<Vec<String>, String> {
+    let data = fs::read_to_string(path).map_err(|e| format!("failed to read {}: {}", path, e))?;
+    let names: Vec<String> = data
+        .lines()
+        .map(|line| line.split('#').next().unwrap_or("").trim())
+        .filter(|name| !name.is_empty())
+        .map(|name| name.to_string())
+        .collect();
+    if names.is_empty() {
+        return Err(format!("{} contains no names", path));
+    }
+    Ok(names)
 }
 
 impl Config {
     fn from_btreemap(config: &BTreeMap<String, String>) -> Self {
-        let names_str = config
-            .get("names")
-            .map(|s| s.as_str())
-            .unwrap_or(DEFAULT_NAMES);
-
-        let names: Vec<String> = names_str
-            .split_whitespace()
-            .map(|s| s.to_string())
-            .collect();
+        let names_file = config.get("names_file").cloned();
+        let from_file = names_file.as_deref().and_then(|path| {
+            read_names_file(path)
+                .map_err(|e| eprintln!("[crew] names_file: {}, falling back to names/pool", e))
+                .ok()
+        });
+
+        // names_file wins over `names`, which wins over `pool`; an empty or unknown pool falls
+        // back to the default
+        let names: Vec<String> = if let Some(from_file) = from_file {
+            from_file
+        } else if let Some(names_str) = config.get("names") {
+            names_str.split_whitespace().map(|s| s.to_string()).collect()
+        } else {
+            let from_pool = config
+                .get("pool")
+                .map(|spec| names_from_pools(spec))
+                .unwrap_or_default();
+            if from_pool.is_empty() {
+                DEFAULT_NAMES.split_whitespace().map(|s| s.to_string()).collect()
+            } else {
+                from_pool
+            }
+        };
+
+        let reserved: Vec<String> = config
+            .get("reserved")
+            .map(|s| s.split_whitespace().map(|s| s.to_string()).collect())
+            .unwrap_or_default();
+
+        let name_template = config.get("name_template").cloned().filter(|template| {
+            let usable = template.contains("{pool}");
+            if !usable {
+                eprintln!("[crew] name_template '{}' has no {{pool}}, ignoring it", template);
+            }
+            usable
+        });
 
         let mode = match config.get("mode").map(|s| s.as_str()) {
             Some("fill-in") => AllocationMode::FillIn,
+            Some("random") => AllocationMode::Random,
+            Some("hash") => AllocationMode::Hash,
             _ => AllocationMode::RoundRobin,
         };
 
+        let seed = config.get("seed").and_then(|s| s.parse().ok());
+
+        let naming = match config.get("naming").map(|s| s.as_str()) {
+            Some("command") => NamingMode::Command,
+            _ => NamingMode::Pool,
+        };
+
+        let on_exhausted = match config.get("on_exhausted").map(|s| s.as_str()) {
+            Some("suffix") => ExhaustedStrategy::Suffix,
+            Some("cycle") => ExhaustedStrategy::Cycle,
+            Some("generate") => ExhaustedStrategy::Generate,
+            _ => ExhaustedStrategy::None,
+        };
+
+        let on_duplicate = match config.get("on_duplicate").map(|s| s.as_str()) {
+            Some("rename-pool") => DuplicateResolution::RenamePool,
+            Some("suffix-newcomer") => DuplicateResolution::SuffixNewcomer,
+            _ => DuplicateResolution::Warn,
+        };
+
+        let show_position = match config.get("show_position").map(|s| s.as_str()) {
+            Some("true") | Some("angle") => Some(PositionFormat::Angle),
+            Some("colon") => Some(PositionFormat::Colon),
+            Some("superscript") => Some(PositionFormat::Superscript),
+            _ => None,
+        };
+
+        let progress_style = match config.get("progress_style").map(|s| s.as_str()) {
+            Some("bar") => ProgressStyle::Bar,
+            _ => ProgressStyle::Percent,
+        };
+
         let hide_swap_layout_indication = config
             .get("hide_swap_layout_indication")
             .map(|s| s == "true")
             .unwrap_or(false);
 
+        // Custom state names travel in "state=NAME" pipe args, so they must be simple words
+        let mut custom_states: Vec<ActivityStatus> = Vec::new();
+        for name in config.get("custom_states").map(|s| s.split_whitespace()).into_iter().flatten() {
+            let valid = name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+            if !valid || ActivityStatus::builtin(name).is_some() {
+                eprintln!("[crew] Ignoring custom state '{}' (built-in name or invalid characters)", name);
+            } else if !custom_states.iter().any(|s| s.status_str() == name) {
+                custom_states.push(ActivityStatus::Custom(name.to_string()));
+            }
+        }
+
         let mut status_indicators = HashMap::new();
-        let status_keys: &[(&str, ActivityStatus)] = &[
-            ("status_unknown", ActivityStatus::Unknown),
-            ("status_idle", ActivityStatus::Idle),
-            ("status_working", ActivityStatus::Working),
-            ("status_question", ActivityStatus::Question),
-            ("status_sleeping", ActivityStatus::Sleeping),
-            ("status_watching", ActivityStatus::Watching),
-            ("status_attention", ActivityStatus::Attention),
-        ];
-        for (key, variant) in status_keys {
-            if let Some(val) = config.get(*key) {
-                status_indicators.insert(variant.clone(), val.clone());
+        let mut sleep_eligible = HashMap::new();
+        let mut priorities = HashMap::new();
+        let mut detect_rules = Vec::new();
+        let mut wake_rules = HashMap::new();
+        for status in ActivityStatus::builtins().iter().chain(&custom_states) {
+            let name = status.status_str();
+            match config.get(&format!("status_{}", name)) {
+                Some(val) => {
+                    status_indicators.insert(status.clone(), val.clone());
+                }
+                // Custom states have no built-in emoji: show the state name
+                None if matches!(status, ActivityStatus::Custom(_)) => {
+                    status_indicators.insert(status.clone(), name.to_string());
+                }
+                None => {}
+            }
+            if let Some(val) = config.get(&format!("sleep_{}", name)) {
+                sleep_eligible.insert(status.clone(), val == "true");
+            }
+            if let Some(val) = config.get(&format!("priority_{}", name)).and_then(|s| s.parse().ok()) {
+                priorities.insert(status.clone(), val);
+            }
+            if let Some(target) = config.get(&format!("wake_{}", name)) {
+                match ActivityStatus::builtins().iter().chain(&custom_states).find(|s| s.status_str() == target) {
+                    Some(target) => {
+                        wake_rules.insert(status.clone(), target.clone());
+                    }
+                    None => eprintln!("[crew] wake_{}: unknown state '{}'", name, target),
+                }
+            }
+            // Multi-line mode so ^ and $ anchor to each line, e.g. a prompt at the end of a line
+            if let Some(pattern) = config.get(&format!("detect_{}", name)) {
+                match RegexBuilder::new(pattern).multi_line(true).build() {
+                    Ok(regex) => detect_rules.push((status.clone(), regex)),
+                    Err(e) => eprintln!("[crew] detect_{}: invalid regex: {}", name, e),
+                }
+            }
+        }
+
+        let detect_lines = config
+            .get("detect_lines")
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(5);
+
+        // `status_priority "attention question working idle"`: listed states outrank every
+        // unlisted one, in list order
+        let order: Vec<&str> = config.get("status_priority")
+            .map(|s| s.split_whitespace().collect())
+            .unwrap_or_default();
+        for (rank, name) in order.iter().enumerate() {
+            match ActivityStatus::builtins().iter().chain(&custom_states).find(|s| s.status_str() == *name) {
+                Some(status) => {
+                    priorities.insert(status.clone(), 1000 - rank as i32);
+                }
+                None => eprintln!("[crew] status_priority: unknown state '{}'", name),
             }
         }
 
@@ -135,17 +486,114 @@ impl Config {
             .and_then(|s| s.parse().ok())
             .unwrap_or(30);
 
+        let stalled_after_secs = config
+            .get("stalled_after_secs")
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(0);
+
+        let heartbeat_timeout_secs = config
+            .get("heartbeat_timeout_secs")
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(0);
+
+        let lost_indicator = config
+            .get("lost_indicator")
+            .cloned()
+            .unwrap_or_else(|| "🔌".to_string());
+
+        let state_option = |key: &str| -> Option<ActivityStatus> {
+            let name = config.get(key)?;
+            let status = ActivityStatus::builtins().iter().chain(&custom_states)
+                .find(|s| s.status_str() == name)
+                .cloned();
+            if status.is_none() {
+                eprintln!("[crew] {}: unknown state '{}'", key, name);
+            }
+            status
+        };
+
+        let stalled_status = state_option("stalled_status").unwrap_or(ActivityStatus::Attention);
+        let on_exit_ok = state_option("on_exit_ok");
+        let on_exit_error = state_option("on_exit_error");
+
+        // A hung agent should be flagged, not quietly put to sleep before stalled_after_secs
+        if stalled_after_secs > 0 {
+            sleep_eligible.entry(ActivityStatus::Working).or_insert(false);
+        }
+
+        let name_quarantine_secs = config
+            .get("name_quarantine_secs")
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(0);
+
+        // "idle" clears on focus, a number of seconds waits that long, anything else never clears
+        let ack_on_focus = match config.get("ack_on_focus").map(|s| s.as_str()) {
+            Some("idle") | Some("0") => AckOnFocus::Immediate,
+            Some(secs) => match secs.parse() {
+                Ok(secs) => AckOnFocus::After(secs),
+                Err(_) => AckOnFocus::Never,
+            },
+            None => AckOnFocus::Never,
+        };
+
         Config {
             names,
+            names_file,
+            reserved,
+            name_template,
             mode,
+            seed,
+            naming,
+            on_exhausted,
+            on_duplicate,
+            show_position,
+            progress_style,
             hide_swap_layout_indication,
             status_indicators,
+            custom_states,
+            sleep_eligible,
+            priorities,
+            wake_rules,
+            detect_rules,
+            detect_lines,
             tell_append,
             tell_delay_ms,
             idle_sleep_secs,
+            stalled_after_secs,
+            stalled_status,
+            heartbeat_timeout_secs,
+            lost_indicator,
+            on_exit_ok,
+            on_exit_error,
+            name_quarantine_secs,
+            ack_on_focus,
         }
     }
 
+    /// True for names that belong to crew rather than the user: pool and reserved names.
+    fn is_pool_name(&self, name: &str) -> bool {
+        self.names.iter().chain(&self.reserved).any(|n| n == name)
+    }
+
+    /// The pool name behind a `name_template` expansion ("work-alpha" -> "alpha" for
+    /// "{session}-{pool}"), with `{n}` matching any number. None if the name isn't one.
+    fn template_pool_name(&self, name: &str, session: Option<&str>) -> Option<String> {
+        let template = self.name_template.as_ref()?;
+        let session = match (template.contains("{session}"), session) {
+            (true, None) => return None,
+            (_, session) => session.unwrap_or_default(),
+        };
+        self.names.iter().find(|pool_name| {
+            let expanded = template.replace("{session}", session).replace("{pool}", pool_name);
+            match expanded.split_once("{n}") {
+                Some((prefix, suffix)) => name.strip_prefix(prefix)
+                    .and_then(|rest| rest.strip_suffix(suffix))
+                    .is_some_and(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit())),
+                None => expanded == name,
+            }
+        }).cloned()
+    }
+
     /// Returns the display string for a status, or None to suppress the indicator entirely.
     fn indicator_for(&self, status: &ActivityStatus) -> Option<&str> {
         match self.status_indicators.get(status) {
@@ -154,6 +602,57 @@ impl Config {
             None => Some(status.default_indicator()),
         }
     }
+
+    /// Parse a state name from a pipe arg: built-in states, then `custom_states`.
+    fn parse_status(&self, name: &str) -> Option<ActivityStatus> {
+        ActivityStatus::builtin(name)
+            .or_else(|| self.custom_states.iter().find(|s| s.status_str() == name).cloned())
+    }
+
+    /// Whether idle_sleep_secs may move a tab in this state to sleeping.
+    fn sleeps(&self, status: &ActivityStatus) -> bool {
+        self.sleep_eligible.get(status).copied().unwrap_or_else(|| status.default_sleeps())
+    }
+
+    fn priority(&self, status: &ActivityStatus) -> i32 {
+        self.priorities.get(status).copied().unwrap_or_else(|| status.default_priority())
+    }
+
+    /// What a pane or tab that auto-slept in `status` wakes up to.
+    fn wake_status(&self, status: &ActivityStatus) -> ActivityStatus {
+        self.wake_rules.get(status).cloned().unwrap_or_else(|| status.clone())
+    }
+
+    /// Match the detect rules against the last `detect_lines` non-blank lines of a viewport,
+    /// trailing whitespace stripped. When several rules match, the highest-priority state wins.
+    fn detect_status(&self, viewport: &[String]) -> Option<ActivityStatus> {
+        if self.detect_rules.is_empty() {
+            return None;
+        }
+        let mut lines: Vec<&str> = viewport.iter()
+            .map(|line| line.trim_end())
+            .filter(|line| !line.is_empty())
+            .rev()
+            .take(self.detect_lines)
+            .collect();
+        lines.reverse();
+        let text = lines.join("\n");
+        self.detect_rules.iter()
+            .filter(|(_, regex)| regex.is_match(&text))
+            .map(|(status, _)| status)
+            .max_by_key(|status| self.priority(status))
+            .cloned()
+    }
+
+    /// The tab-level status for a set of per-pane statuses: the highest priority wins.
+    fn aggregate_status(&self, pane_statuses: &BTreeMap<u32, ActivityStatus>) -> Option<ActivityStatus> {
+        pane_statuses.values().max_by_key(|status| self.priority(status)).cloned()
+    }
+
+    /// Built-in states followed by `custom_states`.
+    fn all_states(&self) -> Vec<ActivityStatus> {
+        ActivityStatus::builtins().into_iter().chain(self.custom_states.iter().cloned()).collect()
+    }
 }
 
 // ============================================================================
@@ -169,9 +668,27 @@ enum ActivityStatus {
     Sleeping,
     Watching,
     Attention,
+    /// Declared in config with `custom_states` (e.g. "testing", "blocked")
+    Custom(String),
 }
 
 impl ActivityStatus {
+    fn builtins() -> [ActivityStatus; 7] {
+        [
+            Self::Unknown,
+            Self::Idle,
+            Self::Working,
+            Self::Question,
+            Self::Sleeping,
+            Self::Watching,
+            Self::Attention,
+        ]
+    }
+
+    fn builtin(name: &str) -> Option<ActivityStatus> {
+        Self::builtins().into_iter().find(|s| s.status_str() == name)
+    }
+
     fn default_indicator(&self) -> &'static str {
         match self {
             Self::Unknown => "🫥",
@@ -181,10 +698,11 @@ impl ActivityStatus {
             Self::Sleeping => "😴",
             Self::Watching => "👀",
             Self::Attention => "🔔",
+            Self::Custom(_) => "•", // Config::from_btreemap defaults custom states to their name
         }
     }
 
-    fn status_str(&self) -> &'static str {
+    fn status_str(&self) -> &str {
         match self {
             Self::Unknown => "unknown",
             Self::Idle => "idle",
@@ -193,6 +711,36 @@ impl ActivityStatus {
             Self::Sleeping => "sleeping",
             Self::Watching => "watching",
             Self::Attention => "attention",
+            Self::Custom(name) => name,
+        }
+    }
+
+    /// States that ask for the user and are cleared by `ack_on_focus`.
+    fn wants_ack(&self) -> bool {
+        matches!(self, Self::Attention | Self::Question)
+    }
+
+    /// States of an agent still at work, where a lapsed heartbeat means it died. A done or
+    /// sleeping agent may stop checking in.
+    fn expects_heartbeat(&self) -> bool {
+        matches!(self, Self::Working | Self::Question | Self::Attention | Self::Custom(_))
+    }
+
+    /// Tabs already asleep or without a known agent have nothing to sleep from.
+    fn default_sleeps(&self) -> bool {
+        !matches!(self, Self::Sleeping | Self::Unknown)
+    }
+
+    /// Higher wins when several states compete for one indicator.
+    fn default_priority(&self) -> i32 {
+        match self {
+            Self::Attention => 60,
+            Self::Question => 50,
+            Self::Working | Self::Custom(_) => 40,
+            Self::Watching => 30,
+            Self::Idle => 20,
+            Self::Sleeping => 10,
+            Self::Unknown => 0,
         }
     }
 }
@@ -203,7 +751,40 @@ impl Default for ActivityStatus {
     }
 }
 
+/// Optional pipe args that ride along with a `state=` update.
+#[derive(Debug, Default)]
+struct StatusOptions {
+    ttl_secs: Option<u64>,            // ttl=SECONDS: revert the status after this long
+    revert: Option<ActivityStatus>,   // revert=STATE: what to revert to (default: the previous status)
+    note: Option<String>,             // note=TEXT: replaces the tab's note ("" clears it)
+    progress: Option<u8>,             // progress=0..100: completion of the running job
+}
+
+/// One entry of a tab's status history (`history` command).
 #[derive(Debug, Clone, Serialize, Deserialize)]
+struct StatusChange {
+    ts: u64,                          // epoch_secs
+    old: ActivityStatus,
+    new: ActivityStatus,
+    via: String,                      // Same values as the event log: name, pane, ttl, auto_sleep, ...
+    pane: Option<u32>,                // Pane that reported it, if any
+}
+
+/// What a tab was doing when auto-sleep took it, restored when terminal output wakes it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct PreSleep {
+    status: ActivityStatus,
+    panes: BTreeMap<u32, ActivityStatus>,  // Panes put to sleep -> their status before
+}
+
+/// A status that expires (set with `ttl=`). Checked by the leader's periodic timer.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StatusTtl {
+    expires_at: u64,                  // epoch_secs
+    revert: ActivityStatus,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 struct CrewTabState {
     tab_id: usize,                   // Stable ID from TabInfo.tab_id (survives reordering)
     position: usize,                 // Current position (updates when tabs reorder)
@@ -218,7 +799,43 @@ struct CrewTabState {
     pending_rename: Option<String>,  // Some("Alice") when rename sent, waiting for confirmation
 
     user_defined: bool,              // true if user named it, false if from our pool
-    status: ActivityStatus,          // Current activity status
+    #[serde(default)]
+    name_source: NameSource,         // Pool, exhaustion fallback, or user
+    #[serde(default)]
+    pool_name: Option<String>,       // Short pool name behind a name_template expansion ("alpha")
+    #[serde(default)]
+    hash_key: Option<String>,        // Hash mode: key the current name was hashed from
+    #[serde(default)]
+    duplicate: bool,                 // Another tab has the same name (on_duplicate "warn")
+    status: ActivityStatus,          // Current activity status (aggregate of pane_statuses)
+    #[serde(default)]
+    pane_statuses: BTreeMap<u32, ActivityStatus>,  // Terminal pane id -> status reported by that pane
+    #[serde(default)]
+    detected_panes: BTreeMap<u32, ActivityStatus>,  // Pane id -> last detect_* match behind its pane_statuses entry
+    #[serde(default)]
+    name_status: bool,               // Current status came from a name=/batch update (no content detection)
+    #[serde(default)]
+    exited_panes: BTreeSet<u32>,     // Terminal panes already seen exited (each exit is applied once)
+    #[serde(default)]
+    exit_code: Option<i32>,          // Exit code of the pane that exited last (None = no code)
+    #[serde(default)]
+    last_heartbeat_at: Option<u64>,  // epoch_secs of the last heartbeat (None = tab never sent one)
+    #[serde(default)]
+    heartbeat_pane: Option<u32>,     // Pane that sent the last heartbeat (None = sent by name)
+    #[serde(default)]
+    lost: bool,                      // Heartbeat lapsed past heartbeat_timeout_secs
+    #[serde(default)]
+    ttl: Option<StatusTtl>,          // Pending revert from a name-addressed ttl= update (whole tab)
+    #[serde(default)]
+    pane_ttls: BTreeMap<u32, StatusTtl>,  // Pane id -> pending revert from that pane's ttl= update
+    #[serde(default)]
+    pre_sleep: Option<PreSleep>,     // Set by auto-sleep, cleared by any other status change
+    #[serde(skip)]
+    history: VecDeque<StatusChange>,  // Last STATUS_HISTORY_LEN transitions, oldest first (handed over on resign)
+    #[serde(default)]
+    progress: Option<u8>,            // 0..=100 from progress=, only while working
+    #[serde(default)]
+    note: Option<String>,            // Free text from note= ("running tests 3/10"), cleared on status change
 
     #[serde(skip)]
     last_msg_to: Option<(u32, u64)>,       // (msg_id, epoch_secs) - last message sent TO this tab
@@ -228,6 +845,45 @@ struct CrewTabState {
     status_updated_at: Option<u64>,        // epoch_secs when status last changed
     #[serde(skip)]
     last_activity_at: Option<u64>,         // epoch_secs of last PaneRenderReport for this tab
+    #[serde(skip)]
+    focused_since: Option<u64>,            // epoch_secs the tab became active (None = not active)
+}
+
+impl CrewTabState {
+    /// Forget heartbeats once the pane that sent them is gone: its agent can't check in anymore.
+    fn clear_heartbeat(&mut self) {
+        self.last_heartbeat_at = None;
+        self.heartbeat_pane = None;
+        self.lost = false;
+    }
+
+    /// Whether any ttl (tab-level or per pane) is waiting to expire.
+    fn has_ttls(&self) -> bool {
+        self.ttl.is_some() || !self.pane_ttls.is_empty()
+    }
+
+    /// Change the tab's status and record the transition in its history. Progress only
+    /// means something while working, so it is dropped on any other status; the pre-sleep
+    /// status and name_status are stale once anything else changes the status.
+    fn set_status(&mut self, status: ActivityStatus, now: u64, via: &str, pane: Option<u32>) {
+        if status != ActivityStatus::Working {
+            self.progress = None;
+        }
+        self.pre_sleep = None;
+        self.name_status = false;
+        if self.history.len() == STATUS_HISTORY_LEN {
+            self.history.pop_front();
+        }
+        self.history.push_back(StatusChange {
+            ts: now,
+            old: self.status.clone(),
+            new: status.clone(),
+            via: via.to_string(),
+            pane,
+        });
+        self.status = status;
+        self.status_updated_at = Some(now);
+    }
 }
 
 #[derive(Default)]
@@ -244,8 +900,17 @@ struct State {
     known_tabs: HashMap<usize, CrewTabState>,  // tab_id -> CrewTabState
     pane_manifest: Option<PaneManifest>,       // For mapping pane_id -> tab
     last_assigned_idx: Option<usize>,
+    rng_state: u64,                   // random mode: splitmix64 state
+    cycle_next: usize,                // on_exhausted "cycle": monotonic pool index across generations
+    generated_next: usize,            // on_exhausted "generate": next adjective-noun index
+    template_next: usize,             // name_template: last {n} handed out
+    closed_names: HashMap<String, u64>,  // Name -> epoch_secs its tab closed (name_quarantine_secs)
+    reported_duplicates: HashMap<String, Vec<usize>>,  // Duplicate name -> tab ids already logged
     inherited_state: Option<HashMap<usize, CrewTabState>>,  // From leader resign
+    persisted_tabs: HashMap<String, CrewTabState>,  // From previous session state file, keyed by name
+    persisted_loaded: bool,           // State file read (needs session name from ModeUpdate)
     pending_tell_enter: Option<u32>,  // Pane ID awaiting delayed \r after tell
+    periodic_armed: bool,             // Periodic check timer (sleep, ttl, ack) is ticking
     next_msg_id: u32,                 // Monotonic counter for tell message IDs
 
     // All instances (for rendering)
@@ -266,6 +931,106 @@ fn epoch_secs() -> u64 {
         .as_secs()
 }
 
+// Shells say nothing about a tab's purpose; tabs running only a shell keep their pool name.
+const SHELL_COMMANDS: &[&str] = &["bash", "zsh", "fish", "sh", "dash", "nu", "elvish", "xonsh"];
+const COMMAND_NAME_MAX_LEN: usize = 24;
+
+// Status transitions kept per tab for the history command (older ones are in the event log)
+const STATUS_HISTORY_LEN: usize = 32;
+
+// Status notes longer than this are cut with "…" in the tab bar (list/state show them in full)
+const NOTE_MAX_CHARS: usize = 24;
+
+/// Whether a pane title says nothing about its purpose: zellij's default "Pane #N", or what
+/// shells put there at the prompt ("user@host: ~/src", "~/src/api"). These change with every
+/// `cd`, so naming tabs after them would rename the tab on each prompt.
+fn is_prompt_title(title: &str) -> bool {
+    let title = title.trim();
+    title.is_empty()
+        || title.strip_prefix("Pane #").is_some_and(|n| n.chars().all(|c| c.is_ascii_digit()))
+        || title.contains('@')
+        || title.starts_with('~')
+        || title.starts_with('/')
+}
+
+/// Derive a tab name from a pane's command, or its title when no command is set:
+/// "make test" -> "make-test", "claude api" -> "claude-api". Returns None for bare shells,
+/// default and prompt titles (see is_prompt_title), and titles with nothing usable.
+fn command_name(pane: &PaneInfo) -> Option<String> {
+    let source = match pane.terminal_command.as_deref() {
+        Some(command) => command,
+        None if is_prompt_title(&pane.title) => return None,
+        None => pane.title.as_str(),
+    };
+    let words: Vec<String> = source
+        .split(|c: char| c.is_whitespace() || c == ':')
+        .filter(|w| !w.is_empty() && !w.starts_with('-'))
+        .map(|w| {
+            w.trim_end_matches('/')
+                .rsplit('/')
+                .next()
+                .unwrap_or(w)
+                .to_lowercase()
+                .chars()
+                .filter(|c| c.is_alphanumeric() || *c == '-' || *c == '_' || *c == '.')
+                .collect::<String>()
+        })
+        .filter(|w| !w.is_empty() && w != "~")
+        .take(2)
+        .collect();
+    let first = words.first()?;
+    if words.len() == 1 && SHELL_COMMANDS.contains(&first.as_str()) {
+        return None;
+    }
+    let mut name = words.join("-");
+    name.truncate(name.char_indices().nth(COMMAND_NAME_MAX_LEN).map(|(i, _)| i).unwrap_or(name.len()));
+    Some(name)
+}
+
+/// FNV-1a: stable across builds and platforms, so a key always hashes to the same pool slot.
+fn fnv1a(key: &str) -> u64 {
+    key.bytes().fold(0xcbf29ce484222325, |hash, b| (hash ^ b as u64).wrapping_mul(0x100000001b3))
+}
+
+fn persisted_state_path(session_name: &str) -> String {
+    let safe: String = session_name
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect();
+    format!("{}/crew-state-{}.json", STATE_DIR, safe)
+}
+
+/// One line of plain text: the note is rendered inside the tab bar.
+fn clean_note(note: &str) -> String {
+    note.chars().map(|c| if c.is_control() { ' ' } else { c }).collect::<String>().trim().to_string()
+}
+
+/// Apply a status update's `note=`: a given note replaces the old one ("" clears it);
+/// without one, the note lasts until the tab's status changes. Returns true if it changed.
+fn apply_note(crew_tab: &mut CrewTabState, note: Option<String>, status_changed: bool) -> bool {
+    let new_note = match note {
+        Some(note) if note.is_empty() => None,
+        Some(note) => Some(note),
+        None if status_changed => None,
+        None => return false,
+    };
+    let changed = crew_tab.note != new_note;
+    crew_tab.note = new_note;
+    changed
+}
+
+/// Apply a status update's `progress=`. Ignored unless the tab (after aggregation) is working;
+/// an update without progress keeps the last value. Returns true if it changed.
+fn apply_progress(crew_tab: &mut CrewTabState, progress: Option<u8>) -> bool {
+    match progress {
+        Some(pct) if crew_tab.status == ActivityStatus::Working && crew_tab.progress != Some(pct) => {
+            crew_tab.progress = Some(pct);
+            true
+        }
+        _ => false,
+    }
+}
+
 /// Rename a tab by its stable tab_id (not position).
 /// Mirrors the shim pattern from zellij-tile but uses RenameTabWithId instead of RenameTab.
 fn rename_tab_with_id(tab_id: usize, name: String) {
@@ -311,6 +1076,14 @@ impl State {
             eprintln!("[crew:{}:leader] Adopting {} inherited tabs", self.instance_id, inherited.len());
             self.known_tabs = inherited;
         }
+        self.load_persisted_state();
+
+        // names_file may have been edited (and reloaded by the old leader) since our load()
+        if let Some(path) = self.config.names_file.clone() {
+            if let Ok(names) = read_names_file(&path) {
+                self.config.names = names;
+            }
+        }
 
         // Broadcast claim so others know
         let payload = serde_json::json!({"plugin_id": self.plugin_id});
@@ -319,10 +1092,8 @@ impl State {
                 .with_payload(serde_json::to_string(&payload).unwrap_or_default()),
         );
 
-        // Arm periodic activity check timer (if idle_sleep_secs enabled)
-        if self.config.idle_sleep_secs > 0 {
-            set_timeout(5.0);
-        }
+        // Arm periodic activity check timer (if idle_sleep_secs enabled or inherited ttls are pending)
+        self.arm_periodic_timer();
 
         self.broadcast_state();
     }
@@ -334,9 +1105,19 @@ impl State {
         eprintln!("[crew:{}:leader] Resigning leadership", self.instance_id);
 
         let state: Vec<&CrewTabState> = self.known_tabs.values().collect();
+        // History stays out of crew-state broadcasts and the state file; only the next leader needs it
+        let history: HashMap<usize, &VecDeque<StatusChange>> = self.known_tabs.iter()
+            .filter(|(_, t)| !t.history.is_empty())
+            .map(|(tab_id, t)| (*tab_id, &t.history))
+            .collect();
         let payload = serde_json::json!({
             "plugin_id": self.plugin_id,
             "state": state,
+            "closed_names": self.closed_names,
+            "rng_state": self.rng_state,
+            "cycle_next": self.cycle_next,
+            "generated_next": self.generated_next,
+            "history": history,
         });
         pipe_message_to_plugin(
             MessageToPlugin::new(MSG_LEADER_RESIGN)
@@ -352,6 +1133,90 @@ impl State {
 // ============================================================================
 
 impl State {
+    /// Read the state file left by this session's previous leader (before resurrection).
+    /// Entries are matched to tabs by name as they appear in handle_leader_tab_update.
+    fn load_persisted_state(&mut self) {
+        if self.persisted_loaded {
+            return;
+        }
+        let session_name = match self.mode_info.session_name.as_deref() {
+            Some(name) => name,
+            None => return, // Retried on ModeUpdate
+        };
+        self.persisted_loaded = true;
+        let path = persisted_state_path(session_name);
+        let data = match fs::read_to_string(&path) {
+            Ok(data) => data,
+            Err(_) => return, // First run for this session
+        };
+        match serde_json::from_str::<Vec<CrewTabState>>(&data) {
+            Ok(tabs) => {
+                eprintln!("[crew:{}:leader] Loaded {} persisted tabs from {}", self.instance_id, tabs.len(), path);
+                self.persisted_tabs = tabs.into_iter().map(|t| (t.name.clone(), t)).collect();
+            }
+            Err(e) => eprintln!("[crew:{}:leader] Failed to parse {}: {}", self.instance_id, path, e),
+        }
+
+        // Tabs seen before the session name was known were tracked without persisted state.
+        // Restore them unless they already have live state (e.g. inherited from a resign).
+        let mut restored_any = false;
+        for crew_tab in self.known_tabs.values_mut() {
+            if crew_tab.pending_rename.is_some() || crew_tab.status_updated_at.is_some() {
+                continue;
+            }
+            if let Some(restored) = self.persisted_tabs.remove(&crew_tab.name) {
+                eprintln!("[crew:{}:leader] Tab {} restored as '{}' ({}, {})",
+                    self.instance_id, crew_tab.tab_id, crew_tab.name,
+                    restored.name_source.as_str(), restored.status.status_str());
+                crew_tab.user_defined = restored.user_defined;
+                crew_tab.name_source = restored.name_source;
+                crew_tab.pool_name = restored.pool_name;
+                crew_tab.status = restored.status;
+                restored_any = true;
+            } else if crew_tab.name_source == NameSource::User {
+                // `{session}` templates couldn't be recognized before the session name was known
+                if let Some(pool_name) = self.config.template_pool_name(&crew_tab.name, Some(session_name)) {
+                    eprintln!("[crew:{}:leader] Tab {} '{}' is a templated pool name ({})",
+                        self.instance_id, crew_tab.tab_id, crew_tab.name, pool_name);
+                    crew_tab.user_defined = false;
+                    crew_tab.name_source = NameSource::Pool;
+                    crew_tab.pool_name = Some(pool_name);
+                    restored_any = true;
+                }
+            }
+        }
+        if !self.known_tabs.is_empty() {
+            self.drop_unclaimed_persisted_tabs();
+        }
+        if restored_any {
+            self.broadcast_state();
+        }
+    }
+
+    /// Forget persisted entries no tab claimed by name. Only once the state file is loaded:
+    /// before that, persisted_tabs is still empty and the tabs are restored on load.
+    fn drop_unclaimed_persisted_tabs(&mut self) {
+        if !self.persisted_loaded || self.persisted_tabs.is_empty() {
+            return;
+        }
+        let mut names: Vec<&String> = self.persisted_tabs.keys().collect();
+        names.sort();
+        eprintln!("[crew:{}:leader] Dropping persisted state for tabs that did not come back: {:?}",
+            self.instance_id, names);
+        self.persisted_tabs.clear();
+    }
+
+    fn persist_state(&self, json: &str) {
+        let session_name = match self.mode_info.session_name.as_deref() {
+            Some(name) => name,
+            None => return,
+        };
+        let path = persisted_state_path(session_name);
+        if let Err(e) = fs::write(&path, json) {
+            eprintln!("[crew:{}:leader] Failed to persist state to {}: {}", self.instance_id, path, e);
+        }
+    }
+
     fn broadcast_state(&self) {
         if !self.is_leader {
             return; // Only leader broadcasts
@@ -361,6 +1226,7 @@ impl State {
 
         if let Ok(json) = serde_json::to_string(&tabs) {
             eprintln!("[crew:{}:leader] Broadcasting state: {} tabs", self.instance_id, tabs.len());
+            self.persist_state(&json);
 
             pipe_message_to_plugin(
                 MessageToPlugin::new("crew-state")
@@ -371,37 +1237,237 @@ impl State {
         }
     }
 
-    fn allocate_from_pool(&mut self) -> Option<String> {
+    /// splitmix64 step for random allocation mode.
+    fn next_random(&mut self) -> u64 {
+        self.rng_state = self.rng_state.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.rng_state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    /// Hash mode: move a crew-allocated tab to the pool name its key hashes to, probing
+    /// forward past names held by other tabs. Claimed, command and user names are kept.
+    fn apply_hash_key(&mut self, tab_id: usize, key: &str) {
+        let crew_tab = match self.known_tabs.get(&tab_id) {
+            Some(t) => t,
+            None => return,
+        };
+        if crew_tab.hash_key.as_deref() == Some(key)
+            || crew_tab.pending_rename.is_some()
+            || matches!(crew_tab.name_source, NameSource::User | NameSource::Claimed | NameSource::Command)
+            || self.config.names.is_empty()
+        {
+            return;
+        }
+        let current = crew_tab.name.clone();
+        let current_pool = crew_tab.pool_name.clone().unwrap_or_else(|| current.clone());
+        let mut taken = self.held_names(Some(tab_id));
+        taken.extend(self.config.reserved.iter().cloned());
+        taken.extend(self.quarantined_names(epoch_secs()).map(|(name, _)| name.clone()));
+        let pool_len = self.config.names.len();
+        let start = (fnv1a(key) % pool_len as u64) as usize;
+        let target_pool = match (0..pool_len)
+            .map(|offset| &self.config.names[(start + offset) % pool_len])
+            .find(|name| !taken.contains(*name))
+        {
+            Some(name) => name.clone(),
+            None => return,
+        };
+        let changed = target_pool != current_pool;
+        let (target, pool_name) = if changed {
+            self.expand_name_template(target_pool)
+        } else {
+            (current.clone(), None)
+        };
+
+        if let Some(crew_tab) = self.known_tabs.get_mut(&tab_id) {
+            crew_tab.hash_key = Some(key.to_string());
+            crew_tab.name_source = NameSource::Pool;
+            if changed {
+                crew_tab.pending_rename = Some(target.clone());
+                crew_tab.pool_name = pool_name;
+            }
+        }
+        if changed {
+            eprintln!("[crew:{}:leader] Tab {} key '{}' hashes to '{}' (was '{}')",
+                self.instance_id, tab_id, key, target, current);
+            rename_tab_with_id(tab_id, target);
+        }
+    }
+
+    /// Names held by tabs other than `except`, as the pool sees them: the tab name (or the
+    /// pending one) plus the short pool name behind a `name_template` expansion.
+    fn held_names(&self, except: Option<usize>) -> HashSet<String> {
+        self.known_tabs
+            .values()
+            .filter(|t| Some(t.tab_id) != except)
+            .flat_map(|t| {
+                let name = t.pending_rename.clone().unwrap_or_else(|| t.name.clone());
+                std::iter::once(name).chain(t.pool_name.clone())
+            })
+            .collect()
+    }
+
+    /// Names whose tab closed less than `name_quarantine_secs` ago, with the seconds since.
+    /// Every allocation path and `claim` skip them so messages for the old tab don't reach a
+    /// new one.
+    fn quarantined_names(&self, now: u64) -> impl Iterator<Item = (&String, u64)> + '_ {
+        self.closed_names.iter()
+            .map(move |(name, closed_at)| (name, now.saturating_sub(*closed_at)))
+            .filter(|(_, ago)| *ago < self.config.name_quarantine_secs)
+    }
+
+    /// Apply `name_template` to a pool name: "alpha" -> "work-alpha" for "{session}-{pool}".
+    /// Returns the tab name and, when a template applied, the short pool name it came from.
+    /// Until ModeUpdate brings the session name, `{session}` templates fall back to the bare name.
+    fn expand_name_template(&mut self, pool_name: String) -> (String, Option<String>) {
+        let template = match &self.config.name_template {
+            Some(t) => t.clone(),
+            None => return (pool_name, None),
+        };
+        let session = match (template.contains("{session}"), self.mode_info.session_name.as_deref()) {
+            (true, None) => return (pool_name, None),
+            (_, session) => session.unwrap_or_default().to_string(),
+        };
+        let mut name = template.replace("{session}", &session).replace("{pool}", &pool_name);
+        if name.contains("{n}") {
+            self.template_next += 1;
+            name = name.replace("{n}", &self.template_next.to_string());
+        }
+        (name, Some(pool_name))
+    }
+
+    fn allocate_from_pool(&mut self) -> Option<(String, Option<String>)> {
         if self.config.names.is_empty() {
             return None;
         }
 
-        let used: HashSet<String> = self
-            .known_tabs
-            .values()
-            .filter(|t| !t.user_defined)
-            .map(|t| t.name.clone())
-            .collect();
+        // User-named tabs count too: handing out a name a user already chose creates a duplicate
+        let mut used = self.held_names(None);
+        used.extend(self.config.reserved.iter().cloned());
+
+        // If only quarantined names are left, the pool counts as exhausted (on_exhausted)
+        let quarantine = self.config.name_quarantine_secs;
+        let now = epoch_secs();
+        self.closed_names.retain(|_, closed_at| now.saturating_sub(*closed_at) < quarantine);
+        used.extend(self.quarantined_names(now).map(|(name, _)| name.clone()));
+        self.pick_pool_name(&used).map(|pool_name| self.expand_name_template(pool_name))
+    }
 
+    /// Pick a pool name not in `used` according to the allocation mode.
+    fn pick_pool_name(&mut self, used: &HashSet<String>) -> Option<String> {
         match self.config.mode {
             AllocationMode::RoundRobin => {
                 let start_idx = self.last_assigned_idx.map(|i| i + 1).unwrap_or(0);
                 let pool_len = self.config.names.len();
 
-                for offset in 0..pool_len {
-                    let idx = (start_idx + offset) % pool_len;
-                    let name = &self.config.names[idx];
-                    if !used.contains(name) {
-                        self.last_assigned_idx = Some(idx);
-                        return Some(name.clone());
+                let idx = (0..pool_len)
+                    .map(|offset| (start_idx + offset) % pool_len)
+                    .find(|idx| !used.contains(&self.config.names[*idx]));
+                if idx.is_some() {
+                    self.last_assigned_idx = idx;
+                }
+                idx.map(|idx| self.config.names[idx].clone())
+            }
+            AllocationMode::Random => {
+                let free: Vec<String> = self.config.names.iter()
+                    .filter(|n| !used.contains(*n))
+                    .cloned()
+                    .collect();
+                if free.is_empty() {
+                    return None;
+                }
+                let pick = (self.next_random() % free.len() as u64) as usize;
+                Some(free[pick].clone())
+            }
+            AllocationMode::FillIn | AllocationMode::Hash => {
+                self.config.names.iter().find(|name| !used.contains(*name)).cloned()
+            }
+        }
+    }
+
+    /// Name derived from the first terminal pane in the tab at `position` (naming "command"),
+    /// made unique against the other tabs with a numeric suffix.
+    fn derive_command_name(&self, tab_id: usize, position: usize) -> Option<String> {
+        let pane = self.pane_manifest.as_ref()?
+            .panes.get(&position)?
+            .iter()
+            .find(|p| !p.is_plugin)?;
+        let base = command_name(pane)?;
+        let taken: HashSet<String> = self
+            .known_tabs
+            .values()
+            .filter(|t| t.tab_id != tab_id)
+            .map(|t| t.pending_rename.clone().unwrap_or_else(|| t.name.clone()))
+            .collect();
+        if !taken.contains(&base) {
+            return Some(base);
+        }
+        (2..taken.len() + 2)
+            .map(|n| format!("{}-{}", base, n))
+            .find(|candidate| !taken.contains(candidate))
+    }
+
+    /// Allocate a name for a new tab: the command name (naming "command"), the pool,
+    /// then the `on_exhausted` fallback. Pool names come with their short form when
+    /// `name_template` applied; other sources are never templated.
+    fn allocate_name(&mut self, tab_id: usize, position: usize) -> Option<(String, NameSource, Option<String>)> {
+        if self.config.naming == NamingMode::Command {
+            if let Some(name) = self.derive_command_name(tab_id, position) {
+                return Some((name, NameSource::Command, None));
+            }
+        }
+        if let Some((name, pool_name)) = self.allocate_from_pool() {
+            return Some((name, NameSource::Pool, pool_name));
+        }
+        if self.config.names.is_empty() && self.config.on_exhausted != ExhaustedStrategy::Generate {
+            return None;
+        }
+
+        // Fallback names must not collide with any tab, including user-named ones, nor reuse
+        // a quarantined name
+        let now = epoch_secs();
+        let used: HashSet<String> = self
+            .known_tabs
+            .values()
+            .map(|t| t.pending_rename.clone().unwrap_or_else(|| t.name.clone()))
+            .chain(self.quarantined_names(now).map(|(name, _)| name.clone()))
+            .collect();
+        let pool_len = self.config.names.len();
+
+        match self.config.on_exhausted {
+            ExhaustedStrategy::None => None,
+            ExhaustedStrategy::Suffix => {
+                // Every generation has pool_len slots, so the first free one is within used.len() + 1 generations
+                (2..used.len() + 3)
+                    .flat_map(|generation| {
+                        self.config.names.iter().map(move |n| format!("{}-{}", n, generation))
+                    })
+                    .find(|candidate| !used.contains(candidate))
+                    .map(|name| (name, NameSource::Suffix, None))
+            }
+            ExhaustedStrategy::Cycle => {
+                for _ in 0..used.len() + pool_len {
+                    let k = self.cycle_next;
+                    self.cycle_next += 1;
+                    let candidate = format!("{}-{}", self.config.names[k % pool_len], k / pool_len + 2);
+                    if !used.contains(&candidate) {
+                        return Some((candidate, NameSource::Cycle, None));
                     }
                 }
                 None
             }
-            AllocationMode::FillIn => {
-                for name in &self.config.names {
-                    if !used.contains(name) {
-                        return Some(name.clone());
+            ExhaustedStrategy::Generate => {
+                let combos = GENERATED_ADJECTIVES.len() * GENERATED_NOUNS.len();
+                for _ in 0..combos {
+                    let k = self.generated_next % combos;
+                    self.generated_next += 1;
+                    let candidate = format!("{}-{}",
+                        GENERATED_ADJECTIVES[k % GENERATED_ADJECTIVES.len()],
+                        GENERATED_NOUNS[k % GENERATED_NOUNS.len()]);
+                    if !used.contains(&candidate) {
+                        return Some((candidate, NameSource::Generated, None));
                     }
                 }
                 None
@@ -414,6 +1480,7 @@ impl State {
 
         // Track which tab IDs we've seen in this update
         let mut seen_tab_ids = HashSet::new();
+        let mut renamed_by_user = HashSet::new();
 
         for tab in tabs {
             let tab_id = tab.tab_id;
@@ -436,7 +1503,10 @@ impl State {
                         self.instance_id, tab_id, crew_tab.name, tab.name, tab.position);
                     crew_tab.name = tab.name.clone();
                     crew_tab.user_defined = true;
+                    crew_tab.name_source = NameSource::User;
+                    crew_tab.pool_name = None;
                     crew_tab.position = tab.position;
+                    renamed_by_user.insert(tab_id);
                 }
                 if crew_tab.position != tab.position {
                     eprintln!("[crew:{}:leader] Tab {} '{}' moved pos {} -> {}",
@@ -447,9 +1517,9 @@ impl State {
                 // New tab
                 if tab.name.starts_with("Tab #") {
                     // Default name - allocate from pool and rename
-                    if let Some(new_name) = self.allocate_from_pool() {
-                        eprintln!("[crew:{}:leader] New tab {}: renaming '{}' -> {} (pos {})",
-                            self.instance_id, tab_id, tab.name, new_name, tab.position);
+                    if let Some((new_name, name_source, pool_name)) = self.allocate_name(tab_id, tab.position) {
+                        eprintln!("[crew:{}:leader] New tab {}: renaming '{}' -> {} (pos {}, {})",
+                            self.instance_id, tab_id, tab.name, new_name, tab.position, name_source.as_str());
                         rename_tab_with_id(tab_id, new_name.clone());
 
                         self.known_tabs.insert(tab_id, CrewTabState {
@@ -458,15 +1528,54 @@ impl State {
                             name: tab.name.clone(),
                             pending_rename: Some(new_name),
                             user_defined: false,
-                            status: ActivityStatus::Unknown,
-                            last_msg_to: None,
-                            last_msg_from: None,
-                            status_updated_at: None,
-                            last_activity_at: None,
+                            name_source,
+                            pool_name,
+                            ..Default::default()
                         });
                     } else {
                         eprintln!("[crew:{}:leader] Pool exhausted, leaving tab {} unnamed", self.instance_id, tab_id);
                     }
+                } else if let Some(restored) = self.persisted_tabs.remove(&tab.name) {
+                    // Resurrected session: zellij kept the name, the state file kept the rest
+                    eprintln!("[crew:{}:leader] New tab {} restored as '{}' ({}, {}) (pos {})",
+                        self.instance_id, tab_id, tab.name, restored.name_source.as_str(),
+                        restored.status.status_str(), tab.position);
+                    self.known_tabs.insert(tab_id, CrewTabState {
+                        tab_id,
+                        position: tab.position,
+                        name: tab.name.clone(),
+                        pending_rename: None,
+                        ..restored
+                    });
+                } else if let Some(pool_name) = self.config.template_pool_name(
+                    &tab.name, self.mode_info.session_name.as_deref(),
+                ) {
+                    // Templated pool name without persisted state - still ours
+                    eprintln!("[crew:{}:leader] New tab {} with templated pool name '{}' ({}) (pos {})",
+                        self.instance_id, tab_id, tab.name, pool_name, tab.position);
+                    self.known_tabs.insert(tab_id, CrewTabState {
+                        tab_id,
+                        position: tab.position,
+                        name: tab.name.clone(),
+                        pending_rename: None,
+                        user_defined: false,
+                        name_source: NameSource::Pool,
+                        pool_name: Some(pool_name),
+                        ..Default::default()
+                    });
+                } else if self.config.is_pool_name(&tab.name) {
+                    // Pool name without persisted state (e.g. state file missing) - still ours
+                    eprintln!("[crew:{}:leader] New tab {} with pool name '{}' (pos {})",
+                        self.instance_id, tab_id, tab.name, tab.position);
+                    self.known_tabs.insert(tab_id, CrewTabState {
+                        tab_id,
+                        position: tab.position,
+                        name: tab.name.clone(),
+                        pending_rename: None,
+                        user_defined: false,
+                        name_source: NameSource::Pool,
+                        ..Default::default()
+                    });
                 } else {
                     // User-defined name on a new tab - track it
                     eprintln!("[crew:{}:leader] New tab {} with user name '{}' (pos {})",
@@ -477,11 +1586,8 @@ impl State {
                         name: tab.name.clone(),
                         pending_rename: None,
                         user_defined: true,
-                        status: ActivityStatus::Unknown,
-                        last_msg_to: None,
-                        last_msg_from: None,
-                        status_updated_at: None,
-                        last_activity_at: None,
+                        name_source: NameSource::User,
+                        ..Default::default()
                     });
                 }
             }
@@ -495,22 +1601,321 @@ impl State {
             .cloned()
             .collect();
 
+        let now = epoch_secs();
         for tab_id in closed {
             if let Some(crew_tab) = self.known_tabs.remove(&tab_id) {
-                if !crew_tab.user_defined {
+                if !crew_tab.user_defined && self.config.name_quarantine_secs > 0 {
+                    eprintln!("[crew:{}:leader] Tab {} '{}' closed, name returns to pool in {}s",
+                        self.instance_id, tab_id, crew_tab.name, self.config.name_quarantine_secs);
+                } else if !crew_tab.user_defined {
                     eprintln!("[crew:{}:leader] Tab {} '{}' closed, name returns to pool", self.instance_id, tab_id, crew_tab.name);
                 } else {
                     eprintln!("[crew:{}:leader] Tab {} '{}' closed (user-defined)", self.instance_id, tab_id, crew_tab.name);
                 }
+                if self.config.name_quarantine_secs > 0 {
+                    self.closed_names.insert(crew_tab.name, now);
+                    if let Some(pool_name) = crew_tab.pool_name {
+                        self.closed_names.insert(pool_name, now);
+                    }
+                }
             }
         }
 
+        self.resolve_duplicate_names(&renamed_by_user);
+
+        // Every resurrected tab has been seen now: leftovers must not attach to a later tab
+        // that happens to get the same name
+        self.drop_unclaimed_persisted_tabs();
+
+        // Track focus for ack_on_focus
+        let mut newly_focused = HashSet::new();
+        for tab in tabs {
+            if let Some(crew_tab) = self.known_tabs.get_mut(&tab.tab_id) {
+                match (tab.active, crew_tab.focused_since) {
+                    (true, None) => {
+                        crew_tab.focused_since = Some(now);
+                        newly_focused.insert(tab.tab_id);
+                    }
+                    (false, Some(_)) => crew_tab.focused_since = None,
+                    _ => {}
+                }
+            }
+        }
+        match self.config.ack_on_focus {
+            AckOnFocus::Never => {}
+            AckOnFocus::Immediate => {
+                self.acknowledge_focused_tabs(now, &newly_focused);
+            }
+            AckOnFocus::After(_) => self.arm_periodic_timer(),
+        }
+
         // Broadcast updated state to renderers
         self.broadcast_state();
     }
 
-    /// Resolve a terminal pane ID to the crew tab name that contains it.
-    fn resolve_pane_name(&self, pane_id: u32) -> Option<String> {
+    /// Detect tabs sharing a name and apply `on_duplicate`. Tabs with a rename in flight are
+    /// skipped; they are checked again once the rename is confirmed.
+    fn resolve_duplicate_names(&mut self, renamed_by_user: &HashSet<usize>) {
+        let mut by_name: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+        for crew_tab in self.known_tabs.values_mut() {
+            crew_tab.duplicate = false;
+            if crew_tab.pending_rename.is_none() {
+                by_name.entry(crew_tab.name.clone()).or_default().push(crew_tab.tab_id);
+            }
+        }
+
+        // Unresolved duplicates come back on every TabUpdate; only a new set is worth a log line
+        let mut reported: HashMap<String, Vec<usize>> = HashMap::new();
+        for (name, mut ids) in by_name {
+            if ids.len() < 2 {
+                continue;
+            }
+            ids.sort();
+            let is_new = self.reported_duplicates.get(&name) != Some(&ids);
+            if is_new {
+                eprintln!("[crew:{}:leader] Duplicate name '{}' on tabs {:?} ({:?})",
+                    self.instance_id, name, ids, self.config.on_duplicate);
+            }
+
+            // The newcomer is the tab the user just renamed, else the most recently created
+            let newcomer = ids.iter().copied()
+                .find(|id| renamed_by_user.contains(id))
+                .unwrap_or(ids[ids.len() - 1]);
+            let pool_held: Vec<usize> = ids.iter().copied()
+                .filter(|id| self.known_tabs.get(id).is_some_and(|t| !t.user_defined))
+                .collect();
+
+            let resolution = match self.config.on_duplicate {
+                DuplicateResolution::RenamePool if !pool_held.is_empty() => {
+                    // If every holder is crew-named, the oldest keeps the name
+                    let yielding: Vec<usize> = if pool_held.len() == ids.len() {
+                        pool_held[1..].to_vec()
+                    } else {
+                        pool_held
+                    };
+                    for tab_id in yielding {
+                        let position = self.known_tabs[&tab_id].position;
+                        if let Some((new_name, name_source, pool_name)) = self.allocate_name(tab_id, position) {
+                            self.rename_duplicate(tab_id, &name, new_name, name_source, pool_name);
+                        }
+                    }
+                    "rename_pool"
+                }
+                // No pool-assigned holder to rename: suffix the newcomer instead
+                DuplicateResolution::RenamePool | DuplicateResolution::SuffixNewcomer => {
+                    let taken: HashSet<String> = self.known_tabs.values()
+                        .map(|t| t.pending_rename.clone().unwrap_or_else(|| t.name.clone()))
+                        .collect();
+                    if let Some(new_name) = (2..taken.len() + 2)
+                        .map(|n| format!("{}-{}", name, n))
+                        .find(|candidate| !taken.contains(candidate))
+                    {
+                        let name_source = self.known_tabs[&newcomer].name_source.clone();
+                        self.rename_duplicate(newcomer, &name, new_name, name_source, None);
+                    }
+                    "suffix_newcomer"
+                }
+                DuplicateResolution::Warn => {
+                    for tab_id in &ids {
+                        if let Some(crew_tab) = self.known_tabs.get_mut(tab_id) {
+                            crew_tab.duplicate = true;
+                        }
+                    }
+                    "warn"
+                }
+            };
+            if is_new {
+                self.log_event(serde_json::json!({
+                    "t": "duplicate", "ts": epoch_secs(),
+                    "name": name, "tabs": ids, "resolution": resolution,
+                }));
+            }
+            reported.insert(name, ids);
+        }
+        self.reported_duplicates = reported;
+    }
+
+    fn rename_duplicate(&mut self, tab_id: usize, old_name: &str, new_name: String,
+                        name_source: NameSource, pool_name: Option<String>) {
+        eprintln!("[crew:{}:leader] Tab {} duplicate '{}' renamed -> {}",
+            self.instance_id, tab_id, old_name, new_name);
+        rename_tab_with_id(tab_id, new_name.clone());
+        if let Some(crew_tab) = self.known_tabs.get_mut(&tab_id) {
+            crew_tab.pending_rename = Some(new_name);
+            crew_tab.name_source = name_source;
+            crew_tab.pool_name = pool_name;
+        }
+    }
+
+    /// Find a tab by name, or by the short pool name behind a `name_template` expansion when
+    /// no tab has that exact name. A name held by several tabs is an error listing their ids,
+    /// so name-addressed commands never act on an arbitrary one of them.
+    fn find_tab_by_name(&self, name: &str, ignore_case: bool) -> Result<usize, String> {
+        let matches = |candidate: &str| {
+            if ignore_case { candidate.eq_ignore_ascii_case(name) } else { candidate == name }
+        };
+        let find = |by_pool_name: bool| -> Vec<usize> {
+            self.known_tabs.values()
+                .filter(|t| if by_pool_name {
+                    t.pool_name.as_deref().is_some_and(matches)
+                } else {
+                    matches(&t.name)
+                })
+                .map(|t| t.tab_id)
+                .collect()
+        };
+        let mut ids = find(false);
+        if ids.is_empty() {
+            ids = find(true);
+        }
+        ids.sort();
+        match ids.as_slice() {
+            // Within the quarantine, say the tab is gone rather than that it never existed
+            [] => match self.quarantined_names(epoch_secs()).find(|(closed, _)| matches(closed)) {
+                Some((closed, ago)) => Err(format!("tab '{}' closed {} seconds ago", closed, ago)),
+                None => Err(format!("tab '{}' not found", name)),
+            },
+            [tab_id] => Ok(*tab_id),
+            _ => Err(format!("name '{}' is ambiguous: tabs {}", name,
+                ids.iter().map(|id| id.to_string()).collect::<Vec<_>>().join(", "))),
+        }
+    }
+
+    /// naming "command": rename crew-named tabs whose command or title now suggests a
+    /// different name. User-named tabs and tabs with a rename in flight are left alone.
+    fn refresh_command_names(&mut self) {
+        let candidates: Vec<(usize, usize, String)> = self
+            .known_tabs
+            .values()
+            .filter(|t| !t.user_defined && t.pending_rename.is_none())
+            .map(|t| (t.tab_id, t.position, t.name.clone()))
+            .collect();
+        for (tab_id, position, current) in candidates {
+            let derived = match self.derive_command_name(tab_id, position) {
+                Some(name) if name != current => name,
+                _ => continue,
+            };
+            eprintln!("[crew:{}:leader] Tab {} purpose changed: renaming '{}' -> {}",
+                self.instance_id, tab_id, current, derived);
+            rename_tab_with_id(tab_id, derived.clone());
+            if let Some(crew_tab) = self.known_tabs.get_mut(&tab_id) {
+                crew_tab.pending_rename = Some(derived);
+                crew_tab.name_source = NameSource::Command;
+                crew_tab.pool_name = None;
+            }
+        }
+    }
+
+    /// Drop statuses of panes that closed, re-aggregating the tab. A tab whose last
+    /// reporting pane is gone keeps its status.
+    fn prune_pane_statuses(&mut self) {
+        let live: HashSet<u32> = match &self.pane_manifest {
+            Some(manifest) => manifest.panes.values()
+                .flatten()
+                .filter(|p| !p.is_plugin)
+                .map(|p| p.id)
+                .collect(),
+            None => return,
+        };
+        let mut changed = false;
+        for crew_tab in self.known_tabs.values_mut() {
+            let before = crew_tab.pane_statuses.len();
+            crew_tab.pane_statuses.retain(|pane_id, _| live.contains(pane_id));
+            crew_tab.detected_panes.retain(|pane_id, _| live.contains(pane_id));
+            crew_tab.exited_panes.retain(|pane_id| live.contains(pane_id));
+            crew_tab.pane_ttls.retain(|pane_id, _| live.contains(pane_id));
+            if crew_tab.heartbeat_pane.is_some_and(|pane_id| !live.contains(&pane_id)) {
+                crew_tab.clear_heartbeat();
+                changed = true;
+            }
+            if crew_tab.pane_statuses.len() == before {
+                continue;
+            }
+            changed = true;
+            if let Some(aggregate) = self.config.aggregate_status(&crew_tab.pane_statuses) {
+                if aggregate != crew_tab.status {
+                    eprintln!("[crew:{}:leader] Tab '{}' lost a pane, status {} -> {}",
+                        self.instance_id, crew_tab.name, crew_tab.status.status_str(), aggregate.status_str());
+                    crew_tab.set_status(aggregate, epoch_secs(), "pane_closed", None);
+                }
+            }
+        }
+        if changed {
+            self.broadcast_state();
+        }
+    }
+
+    /// Apply terminal pane exits from the pane manifest: record the exit code and, with
+    /// on_exit_ok/on_exit_error, report a status for the pane as a hook would.
+    fn apply_pane_exits(&mut self) {
+        let panes: Vec<(usize, u32, bool, Option<i32>)> = match &self.pane_manifest {
+            Some(manifest) => manifest.panes.iter()
+                .flat_map(|(pos, panes)| panes.iter()
+                    .filter(|p| !p.is_plugin)
+                    .map(move |p| (*pos, p.id, p.exited, p.exit_status)))
+                .collect(),
+            None => return,
+        };
+        let now = epoch_secs();
+        let mut exits: Vec<(String, u32, Option<i32>, String, String)> = Vec::new(); // (name, pane, code, old, new)
+        let mut rearmed = false;
+        for (tab_pos, pane_id, exited, exit_status) in panes {
+            let tab_id = match self.tabs.iter().find(|t| t.position == tab_pos) {
+                Some(tab) => tab.tab_id,
+                None => continue,
+            };
+            let crew_tab = match self.known_tabs.get_mut(&tab_id) {
+                Some(crew_tab) => crew_tab,
+                None => continue,
+            };
+            if !exited {
+                // A re-run command pane can exit again; its last exit code no longer applies
+                if crew_tab.exited_panes.remove(&pane_id) {
+                    crew_tab.exit_code = None;
+                    rearmed = true;
+                }
+                continue;
+            }
+            if !crew_tab.exited_panes.insert(pane_id) {
+                continue;
+            }
+            crew_tab.exit_code = exit_status;
+            if crew_tab.heartbeat_pane == Some(pane_id) {
+                crew_tab.clear_heartbeat();
+            }
+            let old = crew_tab.status.status_str().to_string();
+            let reported = match exit_status {
+                Some(0) => self.config.on_exit_ok.clone(),
+                _ => self.config.on_exit_error.clone(),
+            };
+            if let Some(status) = reported {
+                crew_tab.pane_statuses.insert(pane_id, status);
+                crew_tab.detected_panes.remove(&pane_id);
+                let aggregate = self.config.aggregate_status(&crew_tab.pane_statuses)
+                    .expect("pane status just inserted");
+                if aggregate != crew_tab.status {
+                    crew_tab.set_status(aggregate, now, "exit", Some(pane_id));
+                }
+            }
+            eprintln!("[crew:{}:leader] Tab '{}' pane {} exited ({:?}), status {} -> {}",
+                self.instance_id, crew_tab.name, pane_id, exit_status, old, crew_tab.status.status_str());
+            exits.push((crew_tab.name.clone(), pane_id, exit_status, old, crew_tab.status.status_str().to_string()));
+        }
+        for (name, pane, code, old, new) in &exits {
+            self.log_event(serde_json::json!({
+                "t": "status", "ts": now,
+                "name": name, "pane": pane, "exit_code": code,
+                "old": old, "new": new,
+                "changed": old != new, "via": "exit",
+            }));
+        }
+        if !exits.is_empty() || rearmed {
+            self.broadcast_state();
+        }
+    }
+
+    /// Resolve a terminal pane ID to the stable tab_id of the tab that contains it.
+    fn resolve_pane_tab_id(&self, pane_id: u32) -> Option<usize> {
         let manifest = self.pane_manifest.as_ref()?;
         let tab_pos = manifest.panes.iter().find_map(|(pos, panes)| {
             if panes.iter().any(|p| !p.is_plugin && p.id == pane_id) {
@@ -519,14 +1924,185 @@ impl State {
                 None
             }
         })?;
-        let tab = self.tabs.iter().find(|t| t.position == tab_pos)?;
-        self.known_tabs.get(&tab.tab_id).map(|ct| ct.name.clone())
+        self.tabs.iter().find(|t| t.position == tab_pos).map(|t| t.tab_id)
+    }
+
+    /// Resolve the tab a command targets: `pane=ID` (the caller's own tab) or `name=NAME`.
+    fn resolve_target_tab(&self, pipe_message: &PipeMessage) -> Result<usize, String> {
+        if let Some(pane_id_str) = pipe_message.args.get("pane") {
+            let pane_id = pane_id_str.parse::<u32>()
+                .map_err(|_| format!("invalid pane id '{}'", pane_id_str))?;
+            return self.resolve_pane_tab_id(pane_id)
+                .filter(|tab_id| self.known_tabs.contains_key(tab_id))
+                .ok_or_else(|| format!("pane {} not found in any tab", pane_id));
+        }
+        if let Some(name) = pipe_message.args.get("name") {
+            return self.find_tab_by_name(name, false);
+        }
+        Err("missing 'pane' or 'name' argument".to_string())
+    }
+
+    /// `rename=NAME` / `release` / `claim=NAME` on zellij-crew:status. All go through
+    /// rename_tab_with_id and the pending_rename confirmation in handle_leader_tab_update, like
+    /// pool allocation. rename marks the tab user-defined; release hands it back to pool
+    /// allocation; claim takes a specific pool or reserved name.
+    fn handle_rename_command(&mut self, pipe_message: &PipeMessage) -> bool {
+        let result = self.resolve_target_tab(pipe_message).and_then(|tab_id| {
+            if let Some(new_name) = pipe_message.args.get("rename") {
+                self.rename_crew_tab(tab_id, new_name)
+            } else if let Some(claimed) = pipe_message.args.get("claim") {
+                self.claim_crew_tab(tab_id, claimed)
+            } else {
+                self.release_crew_tab(tab_id)
+            }
+        });
+        match result {
+            Ok(msg) => {
+                if let PipeSource::Cli(pipe_id) = &pipe_message.source {
+                    cli_pipe_output(pipe_id, &format!("{}\n", msg));
+                }
+                self.broadcast_state();
+                true
+            }
+            Err(e) => {
+                eprintln!("[crew:{}:leader] Rename command failed: {}", self.instance_id, e);
+                if let PipeSource::Cli(pipe_id) = &pipe_message.source {
+                    cli_pipe_output(pipe_id, &format!("error: {}\n", e));
+                }
+                false
+            }
+        }
+    }
+
+    fn rename_crew_tab(&mut self, tab_id: usize, new_name: &str) -> Result<String, String> {
+        let new_name = new_name.trim();
+        if new_name.is_empty() {
+            return Err("name must not be empty".to_string());
+        }
+        if let Some(other) = self.known_tabs.values().find(|t| {
+            t.tab_id != tab_id
+                && (t.name == new_name || t.pending_rename.as_deref() == Some(new_name))
+        }) {
+            return Err(format!("name '{}' already in use by tab {}", new_name, other.tab_id));
+        }
+        let crew_tab = self.known_tabs.get_mut(&tab_id)
+            .ok_or_else(|| format!("tab {} not found", tab_id))?;
+        let old_name = crew_tab.name.clone();
+        crew_tab.pending_rename = Some(new_name.to_string());
+        crew_tab.user_defined = true;
+        crew_tab.name_source = NameSource::User;
+        crew_tab.pool_name = None;
+        rename_tab_with_id(tab_id, new_name.to_string());
+        eprintln!("[crew:{}:leader] Tab {} renamed by command: '{}' -> '{}'",
+            self.instance_id, tab_id, old_name, new_name);
+        self.log_event(serde_json::json!({
+            "t": "rename", "ts": epoch_secs(),
+            "tab": tab_id, "old": old_name, "new": new_name,
+        }));
+        Ok(format!("renamed '{}' -> '{}'", old_name, new_name))
+    }
+
+    /// `reload-names`: re-read names_file into the pool. Tabs keep their current names even
+    /// if the file no longer lists them; those names just aren't handed out again.
+    fn reload_names(&mut self) -> Result<String, String> {
+        let path = self.config.names_file.clone()
+            .ok_or_else(|| "no names_file configured".to_string())?;
+        let names = read_names_file(&path)?;
+        let orphaned: Vec<String> = self.known_tabs.values()
+            .filter(|t| !t.user_defined && !names.contains(t.pool_name.as_ref().unwrap_or(&t.name)))
+            .map(|t| t.name.clone())
+            .collect();
+        if self.last_assigned_idx.is_some_and(|i| i >= names.len()) {
+            self.last_assigned_idx = None;
+        }
+        eprintln!("[crew:{}:leader] Reloaded {} names from {} (was {})",
+            self.instance_id, names.len(), path, self.config.names.len());
+        self.config.names = names;
+        let mut msg = format!("reloaded {} names from {}", self.config.names.len(), path);
+        if !orphaned.is_empty() {
+            msg.push_str(&format!(" (kept on tabs, no longer in pool: {})", orphaned.join(", ")));
+        }
+        Ok(msg)
+    }
+
+    fn claim_crew_tab(&mut self, tab_id: usize, name: &str) -> Result<String, String> {
+        let name = name.trim();
+        if !self.config.is_pool_name(name) {
+            return Err(format!("'{}' is not a pool or reserved name (use rename instead)", name));
+        }
+        if let Some(holder) = self.known_tabs.values().find(|t| {
+            t.tab_id != tab_id
+                && (t.name == name
+                    || t.pending_rename.as_deref() == Some(name)
+                    || t.pool_name.as_deref() == Some(name))
+        }) {
+            return Err(format!("'{}' is held by tab {}", name, holder.tab_id));
+        }
+        if let Some((_, ago)) = self.quarantined_names(epoch_secs()).find(|(closed, _)| *closed == name) {
+            return Err(format!("tab '{}' closed {} seconds ago", name, ago));
+        }
+        let crew_tab = self.known_tabs.get(&tab_id)
+            .ok_or_else(|| format!("tab {} not found", tab_id))?;
+        let old_name = crew_tab.name.clone();
+        let already = crew_tab.pending_rename.is_none()
+            && crew_tab.pool_name.as_deref().unwrap_or(&old_name) == name;
+        let (new_name, pool_name) = if already {
+            (old_name.clone(), crew_tab.pool_name.clone())
+        } else {
+            self.expand_name_template(name.to_string())
+        };
+        let crew_tab = self.known_tabs.get_mut(&tab_id).expect("tab id checked above");
+        crew_tab.user_defined = false;
+        crew_tab.name_source = NameSource::Claimed;
+        crew_tab.pool_name = pool_name;
+        if already {
+            return Ok(format!("already named '{}'", old_name));
+        }
+        crew_tab.pending_rename = Some(new_name.clone());
+        rename_tab_with_id(tab_id, new_name.clone());
+        eprintln!("[crew:{}:leader] Tab {} claimed '{}' (was '{}')",
+            self.instance_id, tab_id, new_name, old_name);
+        self.log_event(serde_json::json!({
+            "t": "rename", "ts": epoch_secs(),
+            "tab": tab_id, "old": old_name, "new": new_name, "via": "claim",
+        }));
+        Ok(format!("claimed '{}' (was '{}')", new_name, old_name))
+    }
+
+    fn release_crew_tab(&mut self, tab_id: usize) -> Result<String, String> {
+        let old_name = match self.known_tabs.get(&tab_id) {
+            Some(t) if !t.user_defined => {
+                return Err(format!("tab '{}' already has a {} name", t.name, t.name_source.as_str()));
+            }
+            Some(t) => t.name.clone(),
+            None => return Err(format!("tab {} not found", tab_id)),
+        };
+        let position = self.known_tabs[&tab_id].position;
+        let (new_name, name_source, pool_name) = self.allocate_name(tab_id, position)
+            .ok_or_else(|| "name pool exhausted".to_string())?;
+        if let Some(crew_tab) = self.known_tabs.get_mut(&tab_id) {
+            crew_tab.pending_rename = Some(new_name.clone());
+            crew_tab.user_defined = false;
+            crew_tab.name_source = name_source;
+            crew_tab.pool_name = pool_name;
+        }
+        rename_tab_with_id(tab_id, new_name.clone());
+        eprintln!("[crew:{}:leader] Tab {} released to pool: '{}' -> '{}'",
+            self.instance_id, tab_id, old_name, new_name);
+        self.log_event(serde_json::json!({
+            "t": "rename", "ts": epoch_secs(),
+            "tab": tab_id, "old": old_name, "new": new_name, "via": "release",
+        }));
+        Ok(format!("released '{}' -> '{}'", old_name, new_name))
     }
 
     fn handle_external_status_update(&mut self, pipe_message: &PipeMessage) -> bool {
         // Try to parse as JSON first (for name-based routing)
         if let Some(payload) = &pipe_message.payload {
             if let Ok(json) = serde_json::from_str::<serde_json::Value>(payload) {
+                if let Some(entries) = json.as_array() {
+                    return self.handle_status_batch(entries, &pipe_message.source);
+                }
                 if let Some(to_name) = json.get("to").and_then(|v| v.as_str()) {
                     // Name-based message routing
                     eprintln!("[crew:{}:leader] Received message for '{}'", self.instance_id, to_name);
@@ -536,17 +2112,65 @@ impl State {
             }
         }
 
-        // Parse key=value args format: "pane=ID,state=STATUS" or "name=NAME,state=STATUS"
-        if let Some(state_str) = pipe_message.args.get("state") {
+        // Heartbeat: liveness only, never touches the status or status_updated_at
+        let mut recovered = false;
+        if pipe_message.args.contains_key("heartbeat") {
+            match self.resolve_target_tab(pipe_message) {
+                Ok(tab_id) => {
+                    let pane_id = pipe_message.args.get("pane").and_then(|p| p.parse::<u32>().ok());
+                    recovered = self.record_heartbeat(tab_id, pane_id, epoch_secs());
+                    if recovered {
+                        self.broadcast_state();
+                    }
+                }
+                Err(e) => {
+                    eprintln!("[crew:{}:leader] Heartbeat failed: {}", self.instance_id, e);
+                    if let PipeSource::Cli(pipe_id) = &pipe_message.source {
+                        cli_pipe_output(pipe_id, &format!("error: {}\n", e));
+                    }
+                }
+            }
+            if !pipe_message.args.contains_key("state") && !pipe_message.args.contains_key("progress") {
+                return recovered;
+            }
+        }
+
+        // Hash mode: an allocation key (project dir) from `zellij-crew key`, silently ignored otherwise
+        if let Some(key) = pipe_message.args.get("key") {
+            if self.config.mode == AllocationMode::Hash {
+                if let Ok(tab_id) = self.resolve_target_tab(pipe_message) {
+                    self.apply_hash_key(tab_id, key);
+                }
+            }
+            if !pipe_message.args.contains_key("state") && !pipe_message.args.contains_key("progress") {
+                return false;
+            }
+        }
+
+        // Parse key=value args format: "pane=ID,state=STATUS" or "name=NAME,state=STATUS".
+        // A bare "progress=N" reports a running job: it implies state=working.
+        let state_str = pipe_message.args.get("state").map(|s| s.as_str())
+            .or_else(|| pipe_message.args.get("progress").map(|_| "working"));
+        if let Some(state_str) = state_str {
+            let opts = match self.parse_status_options(pipe_message) {
+                Ok(opts) => opts,
+                Err(e) => {
+                    eprintln!("[crew:{}:leader] Bad status update: {}", self.instance_id, e);
+                    if let PipeSource::Cli(pipe_id) = &pipe_message.source {
+                        cli_pipe_output(pipe_id, &format!("error: {}\n", e));
+                    }
+                    return false;
+                }
+            };
             // Try pane ID first
             if let Some(pane_id_str) = pipe_message.args.get("pane") {
                 if let Ok(pane_id) = pane_id_str.parse::<u32>() {
-                    return self.update_pane_status(pane_id, state_str);
+                    return self.update_pane_status(pane_id, state_str, opts, &pipe_message.source);
                 }
             }
             // Try name
             if let Some(name) = pipe_message.args.get("name") {
-                return self.update_name_status(name, state_str);
+                return self.update_name_status(name, state_str, opts, &pipe_message.source);
             }
         }
 
@@ -554,68 +2178,415 @@ impl State {
         false
     }
 
-    fn update_name_status(&mut self, name: &str, state_str: &str) -> bool {
+    fn parse_status_options(&self, pipe_message: &PipeMessage) -> Result<StatusOptions, String> {
+        let args = &pipe_message.args;
+        let ttl_secs = match args.get("ttl") {
+            Some(s) => match s.parse::<u64>() {
+                Ok(secs) if secs > 0 => Some(secs),
+                _ => return Err(format!("invalid ttl '{}' (expected seconds > 0)", s)),
+            },
+            None => None,
+        };
+        let revert = match args.get("revert") {
+            Some(s) => Some(self.config.parse_status(s)
+                .ok_or_else(|| format!("invalid revert state '{}'", s))?),
+            None => None,
+        };
+        if revert.is_some() && ttl_secs.is_none() {
+            return Err("revert requires ttl".to_string());
+        }
+        let note = args.get("note").map(|s| clean_note(s));
+        let progress = match args.get("progress") {
+            Some(s) => match s.trim_end_matches('%').parse::<u8>() {
+                Ok(pct) if pct <= 100 => Some(pct),
+                _ => return Err(format!("invalid progress '{}' (expected 0..100)", s)),
+            },
+            None => None,
+        };
+        Ok(StatusOptions { ttl_secs, revert, note, progress })
+    }
+
+    /// Whether a periodic check has work: auto-sleep, hung-agent detection, pending ttls,
+    /// heartbeats to watch, or a focused tab waiting for a delayed ack_on_focus.
+    fn needs_periodic_check(&self) -> bool {
+        self.config.idle_sleep_secs > 0
+            || self.config.stalled_after_secs > 0
+            || self.known_tabs.values().any(|t| {
+                t.has_ttls()
+                    || (self.config.heartbeat_timeout_secs > 0 && t.last_heartbeat_at.is_some()
+                        && (!t.lost || !t.status.expects_heartbeat()))
+                    || (matches!(self.config.ack_on_focus, AckOnFocus::After(_))
+                        && t.focused_since.is_some() && t.status.wants_ack())
+            })
+    }
+
+    /// Start the periodic timer if a check needs it and it isn't already ticking.
+    fn arm_periodic_timer(&mut self) {
+        if !self.periodic_armed && self.needs_periodic_check() {
+            set_timeout(5.0);
+            self.periodic_armed = true;
+        }
+    }
+
+    /// Record a heartbeat for a tab. Liveness only: the status and its age stay as they are.
+    /// Returns true if the tab was lost and is back.
+    fn record_heartbeat(&mut self, tab_id: usize, pane_id: Option<u32>, now: u64) -> bool {
+        let crew_tab = match self.known_tabs.get_mut(&tab_id) {
+            Some(crew_tab) => crew_tab,
+            None => return false,
+        };
+        let first = crew_tab.last_heartbeat_at.is_none();
+        crew_tab.last_heartbeat_at = Some(now);
+        crew_tab.heartbeat_pane = pane_id;
+        if !crew_tab.lost {
+            if first {
+                self.arm_periodic_timer();
+            }
+            return false;
+        }
+        crew_tab.lost = false;
+        let name = crew_tab.name.clone();
+        eprintln!("[crew:{}:leader] Tab '{}' heartbeat is back", self.instance_id, name);
+        self.log_event(serde_json::json!({
+            "t": "heartbeat", "ts": now, "name": name, "lost": false,
+        }));
+        self.arm_periodic_timer();
+        true
+    }
+
+    /// heartbeat_timeout_secs: mark working tabs whose heartbeat lapsed as lost, and unmark
+    /// lost tabs whose agent has since finished. Returns true if any tab changed.
+    fn check_heartbeats(&mut self, now: u64) -> bool {
+        let timeout = self.config.heartbeat_timeout_secs;
+        let mut changes: Vec<(String, bool, u64)> = Vec::new(); // (name, lost, last heartbeat)
+        for crew_tab in self.known_tabs.values_mut() {
+            let last = match crew_tab.last_heartbeat_at {
+                Some(t) => t,
+                None => continue,
+            };
+            if !crew_tab.status.expects_heartbeat() {
+                if crew_tab.lost {
+                    eprintln!("[crew:{}:leader] Tab '{}' no longer lost: agent is {}",
+                        self.instance_id, crew_tab.name, crew_tab.status.status_str());
+                    crew_tab.lost = false;
+                    changes.push((crew_tab.name.clone(), false, last));
+                }
+                continue;
+            }
+            if !crew_tab.lost && now.saturating_sub(last) >= timeout {
+                eprintln!("[crew:{}:leader] Tab '{}' lost: no heartbeat for {}s",
+                    self.instance_id, crew_tab.name, now - last);
+                crew_tab.lost = true;
+                changes.push((crew_tab.name.clone(), true, last));
+            }
+        }
+        for (name, lost, last) in &changes {
+            self.log_event(serde_json::json!({
+                "t": "heartbeat", "ts": now, "name": name, "lost": lost, "last_heartbeat_at": last,
+            }));
+        }
+        !changes.is_empty()
+    }
+
+    /// stalled_after_secs: move working tabs without terminal output or status change for that
+    /// long to stalled_status. Returns true if any tab changed.
+    fn mark_stalled_tabs(&mut self, now: u64) -> bool {
+        let threshold = self.config.stalled_after_secs;
+        let mut stalled: Vec<(String, String, String)> = Vec::new(); // (name, old, new)
+        for crew_tab in self.known_tabs.values_mut() {
+            if crew_tab.status != ActivityStatus::Working {
+                continue;
+            }
+            let status_stale = crew_tab.status_updated_at
+                .map(|t| now.saturating_sub(t) >= threshold)
+                .unwrap_or(false);
+            // A heartbeat says the agent is alive, even if it is thinking silently
+            let activity_stale = crew_tab.last_activity_at.max(crew_tab.last_heartbeat_at)
+                .map(|t| now.saturating_sub(t) >= threshold)
+                .unwrap_or(true);
+            if !(status_stale && activity_stale) {
+                continue;
+            }
+            for pane_status in crew_tab.pane_statuses.values_mut() {
+                if *pane_status == ActivityStatus::Working {
+                    *pane_status = self.config.stalled_status.clone();
+                }
+            }
+            let new_status = self.config.aggregate_status(&crew_tab.pane_statuses)
+                .unwrap_or_else(|| self.config.stalled_status.clone());
+            if new_status == crew_tab.status {
+                continue;
+            }
+            eprintln!("[crew:{}:leader] Tab '{}' working with no sign of life for {}s, transitioning to {}",
+                self.instance_id, crew_tab.name, threshold, new_status.status_str());
+            stalled.push((crew_tab.name.clone(), crew_tab.status.status_str().to_string(),
+                new_status.status_str().to_string()));
+            crew_tab.set_status(new_status, now, "stalled", None);
+        }
+        for (name, old, new) in &stalled {
+            self.log_event(serde_json::json!({
+                "t": "status", "ts": now,
+                "name": name, "old": old, "new": new,
+                "changed": true, "via": "stalled",
+            }));
+        }
+        !stalled.is_empty()
+    }
+
+    /// ack_on_focus: clear attention/question on tabs the user is looking at. Immediate mode
+    /// acts on tabs that just became active; a delay counts from focus or the status change,
+    /// whichever came later. Returns true if any tab changed.
+    fn acknowledge_focused_tabs(&mut self, now: u64, newly_focused: &HashSet<usize>) -> bool {
+        let mut acked: Vec<(String, String, String)> = Vec::new(); // (name, old, new)
+        for crew_tab in self.known_tabs.values_mut() {
+            let focused_since = match crew_tab.focused_since {
+                Some(since) if crew_tab.status.wants_ack() => since,
+                _ => continue,
+            };
+            let due = match self.config.ack_on_focus {
+                AckOnFocus::Never => false,
+                AckOnFocus::Immediate => newly_focused.contains(&crew_tab.tab_id),
+                AckOnFocus::After(secs) => {
+                    let since = focused_since.max(crew_tab.status_updated_at.unwrap_or(0));
+                    now.saturating_sub(since) >= secs
+                }
+            };
+            if !due {
+                continue;
+            }
+            // Acknowledge the panes too, or the next pane update re-aggregates the old state
+            for pane_status in crew_tab.pane_statuses.values_mut() {
+                if pane_status.wants_ack() {
+                    *pane_status = ActivityStatus::Idle;
+                }
+            }
+            let new_status = self.config.aggregate_status(&crew_tab.pane_statuses)
+                .unwrap_or(ActivityStatus::Idle);
+            eprintln!("[crew:{}:leader] Tab '{}' focused, acknowledged {} -> {}",
+                self.instance_id, crew_tab.name, crew_tab.status.status_str(), new_status.status_str());
+            acked.push((crew_tab.name.clone(), crew_tab.status.status_str().to_string(),
+                new_status.status_str().to_string()));
+            crew_tab.set_status(new_status, now, "focus_ack", None);
+            crew_tab.ttl = None;
+            crew_tab.pane_ttls.clear();
+            crew_tab.note = None;
+        }
+        for (name, old, new) in &acked {
+            self.log_event(serde_json::json!({
+                "t": "status", "ts": now,
+                "name": name, "old": old, "new": new,
+                "changed": true, "via": "focus_ack",
+            }));
+        }
+        !acked.is_empty()
+    }
+
+    /// Revert statuses whose ttl has passed. Each pane's ttl expires on its own, then the
+    /// tab is re-aggregated. Returns true if any tab changed.
+    fn expire_status_ttls(&mut self, now: u64) -> bool {
+        let mut reverted: Vec<(String, Option<u32>, String, String)> = Vec::new(); // (name, pane, old, new)
+        for crew_tab in self.known_tabs.values_mut() {
+            let expired: Vec<u32> = crew_tab.pane_ttls.iter()
+                .filter(|(_, ttl)| ttl.expires_at <= now)
+                .map(|(pane_id, _)| *pane_id)
+                .collect();
+            let mut reverted_pane = None;
+            for pane_id in expired {
+                let ttl = crew_tab.pane_ttls.remove(&pane_id).expect("expired pane ttl");
+                // A closed pane's ttl just goes away with it
+                if let Some(pane_status) = crew_tab.pane_statuses.get_mut(&pane_id) {
+                    *pane_status = ttl.revert;
+                    reverted_pane = Some(pane_id);
+                }
+            }
+            let tab_revert = match &crew_tab.ttl {
+                Some(ttl) if ttl.expires_at <= now => crew_tab.ttl.take().map(|ttl| ttl.revert),
+                _ => None,
+            };
+            if reverted_pane.is_none() && tab_revert.is_none() {
+                continue;
+            }
+            // Panes speak for the tab; the tab-level revert only applies when none do
+            let new_status = match self.config.aggregate_status(&crew_tab.pane_statuses) {
+                Some(aggregate) => aggregate,
+                None => match tab_revert {
+                    Some(revert) => revert,
+                    None => continue,
+                },
+            };
+            let old = crew_tab.status.status_str().to_string();
+            reverted.push((crew_tab.name.clone(), reverted_pane, old, new_status.status_str().to_string()));
+            if crew_tab.status != new_status {
+                eprintln!("[crew:{}:leader] Tab '{}' status ttl expired, {} -> {}",
+                    self.instance_id, crew_tab.name, crew_tab.status.status_str(), new_status.status_str());
+                crew_tab.set_status(new_status, now, "ttl", reverted_pane);
+                crew_tab.note = None;
+            }
+        }
+        for (name, pane, old, new) in &reverted {
+            self.log_event(serde_json::json!({
+                "t": "status", "ts": now,
+                "name": name, "pane": pane, "old": old, "new": new,
+                "changed": old != new, "via": "ttl",
+            }));
+        }
+        !reverted.is_empty()
+    }
+
+    fn update_name_status(&mut self, name: &str, state_str: &str, opts: StatusOptions, source: &PipeSource) -> bool {
         // Parse activity status
-        let new_status = match state_str {
-            "unknown" => ActivityStatus::Unknown,
-            "idle" => ActivityStatus::Idle,
-            "working" => ActivityStatus::Working,
-            "question" => ActivityStatus::Question,
-            "sleeping" => ActivityStatus::Sleeping,
-            "watching" => ActivityStatus::Watching,
-            "attention" => ActivityStatus::Attention,
-            _ => {
+        let new_status = match self.config.parse_status(state_str) {
+            Some(status) => status,
+            None => {
                 eprintln!("[crew:{}:leader] Unrecognized status: {}", self.instance_id, state_str);
+                if let PipeSource::Cli(pipe_id) = source {
+                    cli_pipe_output(pipe_id, &format!("error: unknown state '{}'\n", state_str));
+                }
                 return false;
             }
         };
 
         // Find tab by name
-        let found = if let Some(crew_tab) = self.known_tabs.values_mut().find(|t| t.name == name) {
-            let old = crew_tab.status.status_str();
-            let changed = crew_tab.status != new_status;
-            if changed {
-                crew_tab.status = new_status;
-                crew_tab.status_updated_at = Some(epoch_secs());
-            }
-            Some((old, changed))
-        } else {
-            None
-        };
-        if let Some((old_status, changed)) = found {
+        let found = self.find_tab_by_name(name, false)
+            .map(|tab_id| self.apply_tab_status(tab_id, new_status, &opts, "name"));
+        if let Ok((old_status, changed)) = found {
             self.log_event(serde_json::json!({
                 "t": "status", "ts": epoch_secs(),
                 "name": name, "old": old_status, "new": state_str,
-                "changed": changed, "via": "name",
+                "changed": changed, "via": "name", "ttl": opts.ttl_secs, "note": opts.note,
+                "progress": opts.progress,
             }));
+            self.arm_periodic_timer();
             if changed {
                 eprintln!("[crew:{}:leader] Updating tab '{}' to status: {}", self.instance_id, name, state_str);
                 self.broadcast_state();
                 return true;
             }
-        } else {
+        } else if let Err(e) = found {
             self.log_event(serde_json::json!({
                 "t": "status", "ts": epoch_secs(),
                 "name": name, "new": state_str,
-                "error": "tab not found", "via": "name",
+                "error": e, "via": "name",
             }));
-            eprintln!("[crew:{}:leader] Tab '{}' not found", self.instance_id, name);
+            eprintln!("[crew:{}:leader] Status update for '{}' failed: {}", self.instance_id, name, e);
+            if let PipeSource::Cli(pipe_id) = source {
+                cli_pipe_output(pipe_id, &format!("error: {}\n", e));
+            }
         }
         false
     }
 
-    fn update_pane_status(&mut self, pane_id: u32, state_str: &str) -> bool {
+    /// Set a tab's status as a whole (name-addressed updates). Returns the old status and
+    /// whether anything visible changed; logging and broadcasting are up to the caller.
+    fn apply_tab_status(&mut self, tab_id: usize, new_status: ActivityStatus, opts: &StatusOptions, via: &str) -> (String, bool) {
+        let crew_tab = self.known_tabs.get_mut(&tab_id).expect("tab id from known_tabs");
+        let old = crew_tab.status.status_str().to_string();
+        let changed = crew_tab.status != new_status;
+        // A name-addressed update speaks for the whole tab, replacing per-pane statuses
+        crew_tab.pane_statuses.clear();
+        crew_tab.detected_panes.clear();
+        crew_tab.pane_ttls.clear();
+        crew_tab.ttl = opts.ttl_secs.map(|secs| StatusTtl {
+            expires_at: epoch_secs() + secs,
+            revert: opts.revert.clone().unwrap_or_else(|| crew_tab.status.clone()),
+        });
+        if changed {
+            crew_tab.set_status(new_status, epoch_secs(), via, None);
+        }
+        crew_tab.name_status = true;
+        let note_changed = apply_note(crew_tab, opts.note.clone(), changed);
+        let progress_changed = apply_progress(crew_tab, opts.progress);
+        (old, changed || note_changed || progress_changed)
+    }
+
+    /// JSON payload form: `[{"target": NAME, "state": STATE, "note": TEXT}, ...]`. Every entry
+    /// is checked before any is applied, so the batch changes all its tabs or none, and the
+    /// result goes out in one broadcast. Per-entry results are returned as JSON.
+    fn handle_status_batch(&mut self, entries: &[serde_json::Value], source: &PipeSource) -> bool {
+        let mut planned: Vec<Result<(usize, ActivityStatus, StatusOptions), String>> = entries.iter()
+            .map(|entry| {
+                let target = entry.get("target").and_then(|v| v.as_str())
+                    .ok_or_else(|| "missing 'target'".to_string())?;
+                let state = entry.get("state").and_then(|v| v.as_str())
+                    .ok_or_else(|| "missing 'state'".to_string())?;
+                let status = self.config.parse_status(state)
+                    .ok_or_else(|| format!("unknown state '{}'", state))?;
+                let note = match entry.get("note") {
+                    None | Some(serde_json::Value::Null) => None,
+                    Some(serde_json::Value::String(note)) => Some(clean_note(note)),
+                    Some(_) => return Err("'note' must be a string".to_string()),
+                };
+                let tab_id = self.find_tab_by_name(target, false)?;
+                Ok((tab_id, status, StatusOptions { note, ..Default::default() }))
+            })
+            .collect();
+        // Two entries for one tab (by name or pool name) would make the result depend on order
+        let mut seen: HashSet<usize> = HashSet::new();
+        for plan in planned.iter_mut() {
+            if let Ok((tab_id, _, _)) = plan {
+                if !seen.insert(*tab_id) {
+                    *plan = Err(format!("duplicate target: tab '{}' is already in this batch",
+                        self.known_tabs[tab_id].name));
+                }
+            }
+        }
+        let valid = planned.iter().all(|plan| plan.is_ok());
+
+        let now = epoch_secs();
+        let mut changed = false;
+        let mut results = Vec::new();
+        for (entry, plan) in entries.iter().zip(planned) {
+            let target = entry.get("target").cloned().unwrap_or(serde_json::Value::Null);
+            match plan {
+                Ok((tab_id, status, opts)) if valid => {
+                    let new = status.status_str().to_string();
+                    let (old, entry_changed) = self.apply_tab_status(tab_id, status, &opts, "batch");
+                    changed |= entry_changed;
+                    self.log_event(serde_json::json!({
+                        "t": "status", "ts": now,
+                        "name": self.known_tabs[&tab_id].name, "old": old, "new": new,
+                        "changed": entry_changed, "via": "batch", "note": opts.note,
+                    }));
+                    results.push(serde_json::json!({
+                        "target": target, "ok": true, "old": old, "new": new, "changed": entry_changed,
+                    }));
+                }
+                Ok(_) => results.push(serde_json::json!({
+                    "target": target, "ok": false, "error": "not applied: another entry failed",
+                })),
+                Err(e) => results.push(serde_json::json!({"target": target, "ok": false, "error": e})),
+            }
+        }
+
+        if valid {
+            eprintln!("[crew:{}:leader] Applied status batch of {} entries", self.instance_id, entries.len());
+        } else {
+            eprintln!("[crew:{}:leader] Rejected status batch of {} entries", self.instance_id, entries.len());
+            self.log_event(serde_json::json!({
+                "t": "status", "ts": now, "via": "batch",
+                "error": "rejected", "entries": entries.len(),
+            }));
+        }
+        if changed {
+            self.broadcast_state();
+        }
+        self.arm_periodic_timer();
+        if let PipeSource::Cli(pipe_id) = source {
+            cli_pipe_output(pipe_id, &format!("{}\n",
+                serde_json::to_string_pretty(&results).unwrap_or_else(|_| "[]".to_string())));
+        }
+        changed
+    }
+
+    fn update_pane_status(&mut self, pane_id: u32, state_str: &str, opts: StatusOptions, source: &PipeSource) -> bool {
         // Parse activity status
-        let new_status = match state_str {
-            "unknown" => ActivityStatus::Unknown,
-            "idle" => ActivityStatus::Idle,
-            "working" => ActivityStatus::Working,
-            "question" => ActivityStatus::Question,
-            "sleeping" => ActivityStatus::Sleeping,
-            "watching" => ActivityStatus::Watching,
-            "attention" => ActivityStatus::Attention,
-            _ => {
+        let new_status = match self.config.parse_status(state_str) {
+            Some(status) => status,
+            None => {
                 eprintln!("[crew:{}:leader] Unrecognized status: {}", self.instance_id, state_str);
+                if let PipeSource::Cli(pipe_id) = source {
+                    cli_pipe_output(pipe_id, &format!("error: unknown state '{}'\n", state_str));
+                }
                 return false;
             }
         };
@@ -652,29 +2623,54 @@ impl State {
                 .map(|t| t.tab_id);
 
             if let Some(tab_id) = tab_id {
-                // Update specific tab
-                let (tab_name, old_status, changed) = if let Some(crew_tab) = self.known_tabs.get_mut(&tab_id) {
-                    let old = crew_tab.status.status_str();
-                    let changed = crew_tab.status != new_status;
+                // Record the pane's status; the tab shows the highest-priority pane status
+                let (tab_name, old_status, tab_status, changed) = if let Some(crew_tab) = self.known_tabs.get_mut(&tab_id) {
+                    // A ttl belongs to the pane that set it; that pane's next update replaces it.
+                    // Once a pane speaks, a tab-level ttl from a name update no longer applies.
+                    let previous = crew_tab.pane_statuses.get(&pane_id).unwrap_or(&crew_tab.status).clone();
+                    match opts.ttl_secs {
+                        Some(secs) => {
+                            crew_tab.pane_ttls.insert(pane_id, StatusTtl {
+                                expires_at: epoch_secs() + secs,
+                                revert: opts.revert.clone().unwrap_or(previous),
+                            });
+                        }
+                        None => {
+                            crew_tab.pane_ttls.remove(&pane_id);
+                        }
+                    }
+                    crew_tab.ttl = None;
+                    // A pane that reports its own status is no longer up to the detect rules, and the
+                    // tab's status is per-pane again, so the other panes' content counts once more
+                    crew_tab.pane_statuses.insert(pane_id, new_status);
+                    crew_tab.detected_panes.remove(&pane_id);
+                    crew_tab.name_status = false;
+                    let aggregate = self.config.aggregate_status(&crew_tab.pane_statuses)
+                        .expect("pane status just inserted");
+                    let old = crew_tab.status.status_str().to_string();
+                    let changed = crew_tab.status != aggregate;
                     let name = crew_tab.name.clone();
                     if changed {
-                        crew_tab.status = new_status;
-                        crew_tab.status_updated_at = Some(epoch_secs());
+                        crew_tab.set_status(aggregate, epoch_secs(), "pane", Some(pane_id));
                     }
-                    (name, old, changed)
+                    let note_changed = apply_note(crew_tab, opts.note.clone(), changed);
+                    let progress_changed = apply_progress(crew_tab, opts.progress);
+                    (name, old, crew_tab.status.status_str().to_string(), changed || note_changed || progress_changed)
                 } else {
-                    (String::new(), "", false)
+                    (String::new(), String::new(), String::new(), false)
                 };
                 if !tab_name.is_empty() {
                     self.log_event(serde_json::json!({
                         "t": "status", "ts": epoch_secs(),
                         "name": tab_name, "pane": pane_id,
-                        "old": old_status, "new": state_str,
-                        "changed": changed, "via": "pane",
+                        "old": old_status, "new": state_str, "tab_status": tab_status,
+                        "changed": changed, "via": "pane", "ttl": opts.ttl_secs, "note": opts.note,
+                        "progress": opts.progress,
                     }));
+                    self.arm_periodic_timer();
                     if changed {
-                        eprintln!("[crew:{}:leader] Updating tab '{}' (id={}) to status: {}",
-                            self.instance_id, tab_name, tab_id, state_str);
+                        eprintln!("[crew:{}:leader] Updating tab '{}' (id={}) to status: {} (pane {}: {})",
+                            self.instance_id, tab_name, tab_id, tab_status, pane_id, state_str);
                         self.broadcast_state();
                         return true;
                     }
@@ -731,10 +2727,13 @@ impl State {
             }
         };
 
-        // Resolve sender name from pane ID
-        let sender = pipe_message.args.get("pane")
+        // Resolve sender tab and name from pane ID
+        let sender_tab_id = pipe_message.args.get("pane")
             .and_then(|id_str| id_str.parse::<u32>().ok())
-            .and_then(|id| self.resolve_pane_name(id))
+            .and_then(|id| self.resolve_pane_tab_id(id));
+        let sender = sender_tab_id
+            .and_then(|tab_id| self.known_tabs.get(&tab_id))
+            .map(|t| t.name.clone())
             .unwrap_or_else(|| {
                 pipe_message.args.get("pane")
                     .map(|id| format!("pane {}", id))
@@ -742,13 +2741,14 @@ impl State {
             });
 
         // Find destination tab (case-insensitive), extract values to release borrow
-        let (dest_name, dest_position) = match self.known_tabs.values()
-            .find(|t| t.name.eq_ignore_ascii_case(dest))
-        {
-            Some(t) => (t.name.clone(), t.position),
-            None => {
+        let (dest_tab_id, dest_name, dest_position) = match self.find_tab_by_name(dest, true) {
+            Ok(tab_id) => {
+                let t = &self.known_tabs[&tab_id];
+                (tab_id, t.name.clone(), t.position)
+            }
+            Err(e) => {
                 if let PipeSource::Cli(pipe_id) = &pipe_message.source {
-                    cli_pipe_output(pipe_id, &format!("error: tab '{}' not found\n", dest));
+                    cli_pipe_output(pipe_id, &format!("error: {}\n", e));
                 }
                 return false;
             }
@@ -803,14 +2803,10 @@ impl State {
 
                 // Track message timestamps for state query
                 let now = epoch_secs();
-                if let Some(dest_tab) = self.known_tabs.values_mut()
-                    .find(|t| t.name == dest_name)
-                {
+                if let Some(dest_tab) = self.known_tabs.get_mut(&dest_tab_id) {
                     dest_tab.last_msg_to = Some((msg_id, now));
                 }
-                if let Some(sender_tab) = self.known_tabs.values_mut()
-                    .find(|t| t.name == sender)
-                {
+                if let Some(sender_tab) = sender_tab_id.and_then(|id| self.known_tabs.get_mut(&id)) {
                     sender_tab.last_msg_from = Some((msg_id, now));
                 }
             }
@@ -842,6 +2838,7 @@ impl ZellijPlugin for State {
 
         self.config = Config::from_btreemap(&configuration);
         self.plugin_id = get_plugin_ids().plugin_id;
+        self.rng_state = self.config.seed.unwrap_or(nanos as u64);
 
         eprintln!("[crew:{}:plugin{}] load() config={:?}", self.instance_id, self.plugin_id, configuration);
 
@@ -880,6 +2877,9 @@ impl ZellijPlugin for State {
                     should_render = true;
                 }
                 self.mode_info = mode_info;
+                if self.is_leader {
+                    self.load_persisted_state();
+                }
             }
             Event::TabUpdate(tabs) => {
                 // Fallback: if leader died and we never got a new PermissionRequestResult,
@@ -908,14 +2908,20 @@ impl ZellijPlugin for State {
                 if self.is_leader {
                     // Leader: store pane manifest for pane_id -> tab mapping
                     self.pane_manifest = Some(pane_manifest);
+                    self.prune_pane_statuses();
+                    self.apply_pane_exits();
+                    if self.config.naming == NamingMode::Command {
+                        self.refresh_command_names();
+                    }
                 }
                 should_render = false;
             }
             Event::PaneRenderReport(pane_contents) => {
                 if self.is_leader {
                     let now = epoch_secs();
-                    let mut woke_tabs: Vec<String> = Vec::new();
-                    for pane_id in pane_contents.keys() {
+                    let mut woke_tabs: Vec<(String, String)> = Vec::new(); // (name, new)
+                    let mut detected: Vec<(String, u32, Option<String>, String, String)> = Vec::new(); // (name, pane, pane status, old, new)
+                    for (pane_id, contents) in &pane_contents {
                         if let PaneId::Terminal(id) = pane_id {
                             // Map pane_id -> tab position via pane_manifest
                             let tab_pos = self.pane_manifest.as_ref().and_then(|m| {
@@ -935,33 +2941,92 @@ impl ZellijPlugin for State {
                                 {
                                     if let Some(crew_tab) = self.known_tabs.get_mut(&tab_id) {
                                         crew_tab.last_activity_at = Some(now);
-                                        // Wake sleeping tabs on terminal output
+                                        // Wake sleeping tabs on terminal output, back to what they were doing
+                                        // before auto-sleep (idle if they were put to sleep some other way)
                                         if crew_tab.status == ActivityStatus::Sleeping {
-                                            eprintln!("[crew:{}:leader] Tab '{}' woke from sleeping (terminal activity)",
-                                                self.instance_id, crew_tab.name);
-                                            woke_tabs.push(crew_tab.name.clone());
-                                            crew_tab.status = ActivityStatus::Idle;
-                                            crew_tab.status_updated_at = Some(now);
+                                            let pre_sleep = crew_tab.pre_sleep.take();
+                                            for (pane_id, pane_status) in crew_tab.pane_statuses.iter_mut() {
+                                                if *pane_status == ActivityStatus::Sleeping {
+                                                    *pane_status = pre_sleep.as_ref()
+                                                        .and_then(|p| p.panes.get(pane_id))
+                                                        .map(|before| self.config.wake_status(before))
+                                                        .unwrap_or(ActivityStatus::Idle);
+                                                }
+                                            }
+                                            let new_status = self.config.aggregate_status(&crew_tab.pane_statuses)
+                                                .or_else(|| pre_sleep.map(|p| self.config.wake_status(&p.status)))
+                                                .unwrap_or(ActivityStatus::Idle);
+                                            eprintln!("[crew:{}:leader] Tab '{}' woke from sleeping (terminal activity), restored {}",
+                                                self.instance_id, crew_tab.name, new_status.status_str());
+                                            woke_tabs.push((crew_tab.name.clone(), new_status.status_str().to_string()));
+                                            crew_tab.set_status(new_status, now, "activity_wake", Some(*id));
                                             should_render = true;
                                         }
+                                        // Content rules, for panes that don't report their own status, in tabs
+                                        // whose status wasn't set as a whole by name
+                                        let reports_own = crew_tab.pane_statuses.contains_key(id)
+                                            && !crew_tab.detected_panes.contains_key(id);
+                                        let detects = !reports_own && !crew_tab.name_status;
+                                        let matched = if detects {
+                                            self.config.detect_status(&contents.viewport)
+                                        } else {
+                                            None
+                                        };
+                                        // Only a different match counts: an acknowledged pane keeps showing the
+                                        // text that matched, and must not flip straight back
+                                        if detects && matched != crew_tab.detected_panes.get(id).cloned() {
+                                            // A detected pane that stops matching drops out of the aggregate
+                                            match &matched {
+                                                Some(status) => {
+                                                    crew_tab.pane_statuses.insert(*id, status.clone());
+                                                    crew_tab.detected_panes.insert(*id, status.clone());
+                                                }
+                                                None => {
+                                                    crew_tab.pane_statuses.remove(id);
+                                                    crew_tab.detected_panes.remove(id);
+                                                }
+                                            }
+                                            let aggregate = self.config.aggregate_status(&crew_tab.pane_statuses)
+                                                .unwrap_or(ActivityStatus::Idle);
+                                            let pane_status = matched.as_ref().map(|status| status.status_str().to_string());
+                                            let old = crew_tab.status.status_str().to_string();
+                                            if aggregate != crew_tab.status {
+                                                eprintln!("[crew:{}:leader] Tab '{}' pane {} content matched {}, status {} -> {}",
+                                                    self.instance_id, crew_tab.name, id, pane_status.as_deref().unwrap_or("nothing"),
+                                                    old, aggregate.status_str());
+                                                crew_tab.set_status(aggregate, now, "content", Some(*id));
+                                                should_render = true;
+                                            }
+                                            detected.push((crew_tab.name.clone(), *id, pane_status,
+                                                old, crew_tab.status.status_str().to_string()));
+                                        }
                                     }
                                 }
                             }
                         }
                     }
-                    if !woke_tabs.is_empty() {
-                        for name in &woke_tabs {
-                            self.log_event(serde_json::json!({
-                                "t": "status", "ts": now,
-                                "name": name, "old": "sleeping", "new": "idle",
-                                "changed": true, "via": "activity_wake",
-                            }));
-                        }
+                    for (name, new) in &woke_tabs {
+                        self.log_event(serde_json::json!({
+                            "t": "status", "ts": now,
+                            "name": name, "old": "sleeping", "new": new,
+                            "changed": true, "via": "activity_wake",
+                        }));
+                    }
+                    for (name, pane, pane_status, old, new) in &detected {
+                        self.log_event(serde_json::json!({
+                            "t": "status", "ts": now,
+                            "name": name, "pane": pane,
+                            "old": old, "new": pane_status, "tab_status": new,
+                            "changed": old != new, "via": "content",
+                        }));
+                    }
+                    if !woke_tabs.is_empty() || !detected.is_empty() {
                         self.broadcast_state();
                     }
                 }
             }
             Event::Timer(_) => {
+                self.periodic_armed = false;
                 if let Some(pane_id) = self.pending_tell_enter.take() {
                     write_to_pane_id(vec![b'\r'], PaneId::Terminal(pane_id));
                 }
@@ -975,16 +3040,42 @@ impl ZellijPlugin for State {
                     }
                     should_render = true;
                 }
+                // Status ttls: revert before the sleep check so a reverted status gets a fresh idle clock
+                if self.is_leader && self.known_tabs.values().any(|t| t.has_ttls())
+                    && self.expire_status_ttls(epoch_secs())
+                {
+                    self.broadcast_state();
+                    should_render = true;
+                }
+                // Delayed ack_on_focus
+                if self.is_leader && matches!(self.config.ack_on_focus, AckOnFocus::After(_))
+                    && self.acknowledge_focused_tabs(epoch_secs(), &HashSet::new())
+                {
+                    self.broadcast_state();
+                    should_render = true;
+                }
+                // Heartbeat check: flag tabs whose agent stopped checking in
+                if self.is_leader && self.config.heartbeat_timeout_secs > 0
+                    && self.check_heartbeats(epoch_secs())
+                {
+                    self.broadcast_state();
+                    should_render = true;
+                }
+                // Hung-agent check: silent working tabs go to stalled_status instead of sleeping
+                if self.is_leader && self.config.stalled_after_secs > 0
+                    && self.mark_stalled_tabs(epoch_secs())
+                {
+                    self.broadcast_state();
+                    should_render = true;
+                }
                 // Periodic activity check: transition stale tabs to sleeping
                 if self.is_leader && self.config.idle_sleep_secs > 0 {
                     let now = epoch_secs();
                     let threshold = self.config.idle_sleep_secs;
                     let mut changed = false;
-                    let mut slept_tabs: Vec<(String, String)> = Vec::new(); // (name, old_status)
+                    let mut slept_tabs: Vec<(String, String, String)> = Vec::new(); // (name, old, new)
                     for crew_tab in self.known_tabs.values_mut() {
-                        if crew_tab.status == ActivityStatus::Sleeping
-                            || crew_tab.status == ActivityStatus::Unknown
-                        {
+                        if !self.config.sleeps(&crew_tab.status) {
                             continue;
                         }
                         let status_stale = crew_tab.status_updated_at
@@ -994,28 +3085,47 @@ impl ZellijPlugin for State {
                             .map(|t| now.saturating_sub(t) >= threshold)
                             .unwrap_or(true);
                         if status_stale && activity_stale {
+                            // Panes in states that never sleep keep the tab awake through the aggregate
+                            let mut slept_panes = BTreeMap::new();
+                            for (pane_id, pane_status) in crew_tab.pane_statuses.iter_mut() {
+                                if self.config.sleeps(pane_status) {
+                                    slept_panes.insert(*pane_id, pane_status.clone());
+                                    *pane_status = ActivityStatus::Sleeping;
+                                }
+                            }
+                            let new_status = self.config.aggregate_status(&crew_tab.pane_statuses)
+                                .unwrap_or(ActivityStatus::Sleeping);
+                            if new_status == crew_tab.status {
+                                continue;
+                            }
                             let old = crew_tab.status.status_str().to_string();
-                            eprintln!("[crew:{}:leader] Tab '{}' idle too long, transitioning to sleeping",
-                                self.instance_id, crew_tab.name);
-                            slept_tabs.push((crew_tab.name.clone(), old));
-                            crew_tab.status = ActivityStatus::Sleeping;
-                            crew_tab.status_updated_at = Some(now);
+                            eprintln!("[crew:{}:leader] Tab '{}' idle too long, transitioning to {}",
+                                self.instance_id, crew_tab.name, new_status.status_str());
+                            slept_tabs.push((crew_tab.name.clone(), old, new_status.status_str().to_string()));
+                            let pre_sleep = PreSleep { status: crew_tab.status.clone(), panes: slept_panes };
+                            let asleep = new_status == ActivityStatus::Sleeping;
+                            crew_tab.set_status(new_status, now, "auto_sleep", None);
+                            if asleep {
+                                crew_tab.pre_sleep = Some(pre_sleep);
+                            }
                             changed = true;
                         }
                     }
                     if changed {
-                        for (name, old) in &slept_tabs {
+                        for (name, old, new) in &slept_tabs {
                             self.log_event(serde_json::json!({
                                 "t": "status", "ts": now,
-                                "name": name, "old": old, "new": "sleeping",
+                                "name": name, "old": old, "new": new,
                                 "changed": true, "via": "auto_sleep",
                             }));
                         }
                         self.broadcast_state();
                         should_render = true;
                     }
-                    // Re-arm periodic timer
-                    set_timeout(5.0);
+                }
+                // Re-arm periodic timer while any check still has work
+                if self.is_leader {
+                    self.arm_periodic_timer();
                 }
             }
             Event::BeforeClose => {
@@ -1150,12 +3260,38 @@ impl ZellijPlugin for State {
                         // Parse inherited state
                         if let Some(state_val) = msg.get("state") {
                             if let Ok(tabs) = serde_json::from_value::<Vec<CrewTabState>>(state_val.clone()) {
-                                let map: HashMap<usize, CrewTabState> = tabs.into_iter()
+                                let mut map: HashMap<usize, CrewTabState> = tabs.into_iter()
                                     .map(|t| (t.tab_id, t))
                                     .collect();
+                                if let Some(history) = msg.get("history")
+                                    .and_then(|v| serde_json::from_value::<HashMap<usize, VecDeque<StatusChange>>>(v.clone()).ok())
+                                {
+                                    for (tab_id, changes) in history {
+                                        if let Some(crew_tab) = map.get_mut(&tab_id) {
+                                            crew_tab.history = changes;
+                                        }
+                                    }
+                                }
                                 self.inherited_state = Some(map);
                             }
                         }
+                        // The name quarantine outlives the leader that started it
+                        if let Some(closed) = msg.get("closed_names")
+                            .and_then(|v| serde_json::from_value::<HashMap<String, u64>>(v.clone()).ok())
+                        {
+                            self.closed_names = closed;
+                        }
+                        // So are the allocation counters, or seeded and fallback naming would
+                        // restart from scratch under the next leader
+                        if let Some(rng_state) = msg["rng_state"].as_u64() {
+                            self.rng_state = rng_state;
+                        }
+                        if let Some(cycle_next) = msg["cycle_next"].as_u64() {
+                            self.cycle_next = cycle_next as usize;
+                        }
+                        if let Some(generated_next) = msg["generated_next"].as_u64() {
+                            self.generated_next = generated_next as usize;
+                        }
                         // Start new election
                         self.start_election();
                     }
@@ -1197,37 +3333,62 @@ impl ZellijPlugin for State {
 
             if is_help {
                 if let PipeSource::Cli(pipe_id) = &pipe_message.source {
-                    let help = r#"zellij-crew:status - Update tab activity status
+                    let mut states = String::new();
+                    for status in self.config.all_states() {
+                        let description = match status {
+                            ActivityStatus::Unknown => "No status / agent exited",
+                            ActivityStatus::Idle => "Agent idle",
+                            ActivityStatus::Working => "Agent working",
+                            ActivityStatus::Question => "Agent has a question",
+                            ActivityStatus::Sleeping => "Agent sleeping/paused",
+                            ActivityStatus::Watching => "Agent watching/monitoring",
+                            ActivityStatus::Attention => "Needs attention",
+                            ActivityStatus::Custom(_) => "Custom (custom_states)",
+                        };
+                        states.push_str(&format!("  {:<10}{:<4}{:<27}priority {}{}\n",
+                            status.status_str(),
+                            self.config.indicator_for(&status).unwrap_or("-"),
+                            description,
+                            self.config.priority(&status),
+                            if self.config.sleeps(&status) { "" } else { ", never sleeps" }));
+                    }
+                    let help = format!(r#"zellij-crew:status - Update tab activity status
 
 Usage:
   zellij pipe --name zellij-crew:status --args "pane=PANE_ID,state=STATE"
   zellij pipe --name zellij-crew:status --args "name=NAME,state=STATE"
+  Optional: ttl=SECONDS, revert=STATE, note=TEXT, progress=0..100
+  zellij pipe --name zellij-crew:status --args "pane=PANE_ID,progress=42"   (implies working)
+  zellij pipe --name zellij-crew:status --args "pane=PANE_ID,heartbeat"     (liveness, see heartbeat_timeout_secs)
+  zellij pipe --name zellij-crew:status -- '[{{"target":"NAME","state":"STATE","note":"TEXT"}}, ...]'
 
 States:
-  unknown   🫥  No status / agent exited
-  idle      🥱  Agent idle
-  working   🤖  Agent working
-  question  🙋  Agent has a question
-  sleeping  😴  Agent sleeping/paused
-  watching  👀  Agent watching/monitoring
-  attention 🔔  Needs attention
-
+{states}
 Config (in plugin KDL):
   status_unknown ""        Hide indicator when unknown
   status_working "WRK"     Custom text shown as [WRK]
   (set any status_* to "" to suppress the [brackets] entirely)
+  custom_states "testing blocked"   Extra states (indicator defaults to the name)
+  sleep_testing "false"    Keep a state out of idle_sleep_secs auto-sleep
+  priority_testing "55"    Rank of a state when several compete
+  detect_idle "[$#]$"      Regex on a pane's last lines that means a state
 
 Commands:
   --args help               Show this help
   --args list               List all tabs (alias: ls)
   --args format=json,list   Output in JSON format
   --args format=json,state  Detailed per-tab state (pane info, msg tracking)
+  --args pane=ID,rename=NAME  Rename the pane's tab (marks it user-named)
+  --args pane=ID,release      Give a user-named tab a name from the pool again
+  --args pane=ID,claim=NAME   Take a specific pool or reserved name for the pane's tab
+  --args reload-names         Re-read names_file (assigned names stay valid)
+  --args history=NAME         Recent status changes of a tab (format=json for JSON)
 
 Examples:
   zellij pipe --name zellij-crew:status --args "pane=$ZELLIJ_PANE_ID,state=working"
   zellij pipe --name zellij-crew:status --args "name=Alice,state=attention"
-"#;
-                    cli_pipe_output(pipe_id, help);
+"#);
+                    cli_pipe_output(pipe_id, &help);
                 }
                 return false;
             }
@@ -1248,39 +3409,31 @@ Examples:
                     let output = if want_json {
                         // JSON format
                         let json_tabs: Vec<_> = tabs.iter().map(|tab| {
-                            let status_str = match tab.status {
-                                ActivityStatus::Unknown => "unknown",
-                                ActivityStatus::Idle => "idle",
-                                ActivityStatus::Working => "working",
-                                ActivityStatus::Question => "question",
-                                ActivityStatus::Sleeping => "sleeping",
-                                ActivityStatus::Watching => "watching",
-                                ActivityStatus::Attention => "attention",
-                            };
                             serde_json::json!({
                                 "id": tab.tab_id,
                                 "pos": tab.position,
                                 "name": tab.name,
-                                "status": status_str
+                                "status": tab.status.status_str(),
+                                "progress": tab.progress,
+                                "note": tab.note,
                             })
                         }).collect();
                         format!("{}\n", serde_json::to_string_pretty(&json_tabs).unwrap_or_else(|_| "[]".to_string()))
                     } else {
                         // Human-readable format
-                        let mut out = String::from("ID\tName\tStatus\n");
-                        out.push_str("--\t----\t------\n");
+                        let mut out = String::from("ID\tName\tStatus\tNote\n");
+                        out.push_str("--\t----\t------\t----\n");
 
                         for tab in tabs {
-                            let status_str = match tab.status {
-                                ActivityStatus::Unknown => "🫥 unknown",
-                                ActivityStatus::Idle => "🥱 idle",
-                                ActivityStatus::Working => "🤖 working",
-                                ActivityStatus::Question => "🙋 question",
-                                ActivityStatus::Sleeping => "😴 sleeping",
-                                ActivityStatus::Watching => "👀 watching",
-                                ActivityStatus::Attention => "🔔 attention",
+                            let mut status_str = match self.config.indicator_for(&tab.status) {
+                                Some(ind) => format!("{} {}", ind, tab.status.status_str()),
+                                None => tab.status.status_str().to_string(),
                             };
-                            out.push_str(&format!("{}\t{}\t{}\n", tab.tab_id, tab.name, status_str));
+                            if let Some(pct) = tab.progress {
+                                status_str.push_str(&format!(" {}%", pct));
+                            }
+                            out.push_str(&format!("{}\t{}\t{}\t{}\n", tab.tab_id, tab.name, status_str,
+                                tab.note.as_deref().unwrap_or("")));
                         }
 
                         if self.known_tabs.is_empty() {
@@ -1294,6 +3447,23 @@ Examples:
                 return false;
             }
 
+            // Reload names_file without restarting zellij
+            if pipe_message.args.contains_key("reload-names")
+                || pipe_message.payload.as_deref() == Some("reload-names")
+            {
+                let output = match self.reload_names() {
+                    Ok(msg) => format!("{}\n", msg),
+                    Err(e) => {
+                        eprintln!("[crew:{}:leader] reload-names failed: {}", self.instance_id, e);
+                        format!("error: {}\n", e)
+                    }
+                };
+                if let PipeSource::Cli(pipe_id) = &pipe_message.source {
+                    cli_pipe_output(pipe_id, &output);
+                }
+                return false;
+            }
+
             // State command - detailed per-tab state for agent coordination
             // Triggered by: --args "format=json,state" (with no pane/name keys)
             // or explicitly: --args "state_query" (used by CLI)
@@ -1339,7 +3509,18 @@ Examples:
                             "id": tab.tab_id,
                             "pos": tab.position,
                             "name": tab.name,
+                            "name_source": tab.name_source.as_str(),
+                            "pool_name": tab.pool_name,
+                            "duplicate": tab.duplicate,
                             "status": status_str,
+                            "progress": tab.progress,
+                            "note": tab.note,
+                            "pane_statuses": tab.pane_statuses.iter()
+                                .map(|(id, status)| (id.to_string(), status.status_str()))
+                                .collect::<BTreeMap<_, _>>(),
+                            "exit_code": tab.exit_code,
+                            "last_heartbeat_at": tab.last_heartbeat_at,
+                            "lost": tab.lost,
                             "status_updated_at": tab.status_updated_at,
                             "last_activity_at": tab.last_activity_at,
                             "last_msg_to": msg_to,
@@ -1356,6 +3537,56 @@ Examples:
                 return false;
             }
 
+            // History command - recent status transitions of one tab
+            if let Some(name) = pipe_message.args.get("history") {
+                if let PipeSource::Cli(pipe_id) = &pipe_message.source {
+                    let want_json = pipe_message.args.get("format").map(|s| s.as_str()) == Some("json");
+                    let tab = self.find_tab_by_name(name, true)
+                        .map(|tab_id| &self.known_tabs[&tab_id]);
+
+                    let output = match tab {
+                        Err(e) => format!("error: {}\n", e),
+                        Ok(tab) if want_json => {
+                            let entries: Vec<_> = tab.history.iter().map(|change| {
+                                serde_json::json!({
+                                    "ts": change.ts,
+                                    "old": change.old.status_str(),
+                                    "new": change.new.status_str(),
+                                    "via": change.via,
+                                    "pane": change.pane,
+                                })
+                            }).collect();
+                            format!("{}\n", serde_json::to_string_pretty(&entries)
+                                .unwrap_or_else(|_| "[]".to_string()))
+                        }
+                        Ok(tab) => {
+                            let now = epoch_secs();
+                            let mut out = String::from("Age\tChange\tVia\tPane\n");
+                            out.push_str("---\t------\t---\t----\n");
+                            for change in &tab.history {
+                                out.push_str(&format!("{}s ago\t{} -> {}\t{}\t{}\n",
+                                    now.saturating_sub(change.ts),
+                                    change.old.status_str(), change.new.status_str(), change.via,
+                                    change.pane.map(|id| id.to_string()).unwrap_or_default()));
+                            }
+                            if tab.history.is_empty() {
+                                out.push_str("(no status changes)\n");
+                            }
+                            out
+                        }
+                    };
+                    cli_pipe_output(pipe_id, &output);
+                }
+                return false;
+            }
+
+            if pipe_message.args.contains_key("rename")
+                || pipe_message.args.contains_key("release")
+                || pipe_message.args.contains_key("claim")
+            {
+                return self.handle_rename_command(&pipe_message);
+            }
+
             return self.handle_external_status_update(&pipe_message);
         }
 
@@ -1386,18 +3617,50 @@ Examples:
                     self.received_tabs.iter().find(|ct| ct.tab_id == tab.tab_id)
                 };
 
-                if let Some(crew_tab) = crew_state {
-                    match self.config.indicator_for(&crew_tab.status) {
-                        Some(ind) => format!("{} [{}]", crew_tab.name, ind),
-                        None => crew_tab.name.clone(),
-                    }
-                } else {
+                let (name, status) = match crew_state {
+                    Some(crew_tab) => (crew_tab.name.as_str(), &crew_tab.status),
                     // No crew state yet, use Unknown's indicator config
-                    match self.config.indicator_for(&ActivityStatus::Unknown) {
-                        Some(ind) => format!("{} [{}]", tab.name, ind),
-                        None => tab.name.to_string(),
-                    }
+                    None => (tab.name.as_str(), &ActivityStatus::Unknown),
+                };
+                let mut label = match &self.config.show_position {
+                    Some(format) => format.label(name, tab.position),
+                    None => name.to_string(),
+                };
+                if crew_state.is_some_and(|ct| ct.duplicate) {
+                    label.push_str(" ⚠");
                 }
+                if crew_state.is_some_and(|ct| ct.lost) && !self.config.lost_indicator.is_empty() {
+                    label.push(' ');
+                    label.push_str(&self.config.lost_indicator);
+                }
+                let progress = crew_state
+                    .and_then(|ct| ct.progress)
+                    .map(|pct| self.config.progress_style.format(pct));
+                match (self.config.indicator_for(status), progress) {
+                    (Some(ind), Some(progress)) => format!("{} [{} {}]", label, ind, progress),
+                    (Some(ind), None) => format!("{} [{}]", label, ind),
+                    (None, Some(progress)) => format!("{} [{}]", label, progress),
+                    (None, None) => label,
+                }
+            })
+            .collect();
+
+        let notes: Vec<Option<String>> = self.tabs
+            .iter()
+            .map(|tab| {
+                let crew_state = if self.is_leader {
+                    self.known_tabs.get(&tab.tab_id)
+                } else {
+                    self.received_tabs.iter().find(|ct| ct.tab_id == tab.tab_id)
+                };
+                crew_state.and_then(|ct| ct.note.as_deref()).map(|note| {
+                    if note.chars().count() > NOTE_MAX_CHARS {
+                        let cut: String = note.chars().take(NOTE_MAX_CHARS - 1).collect();
+                        format!("{}…", cut.trim_end())
+                    } else {
+                        note.to_string()
+                    }
+                })
             })
             .collect();
 
@@ -1425,6 +3688,40 @@ Examples:
             all_tabs.push(tab);
         }
 
+        // Notes only where they fit without collapsing tabs: active tab first, then in order
+        let mut room = cols.saturating_sub(1)
+            .saturating_sub(non_tab_len(
+                self.mode_info.session_name.as_deref(),
+                cols.saturating_sub(1),
+                self.mode_info.style.colors,
+                self.mode_info.capabilities,
+                self.mode_info.style.hide_session_name,
+                self.tabs.iter().find(|t| t.active),
+                &self.mode_info,
+                self.config.hide_swap_layout_indication,
+            ))
+            .saturating_sub(all_tabs.iter().map(|t| t.len).sum());
+        let mut order: Vec<usize> = (0..self.tabs.len()).collect();
+        order.sort_by_key(|i| !self.tabs[*i].active);
+        for i in order {
+            let note = match &notes[i] {
+                Some(note) => note,
+                None => continue,
+            };
+            let tab = tab_style(
+                format!("{} {}", names[i], note),
+                &self.tabs[i],
+                i % 2 == 1,
+                self.mode_info.style.colors,
+                self.mode_info.capabilities,
+            );
+            let extra = tab.len.saturating_sub(all_tabs[i].len);
+            if extra <= room {
+                room -= extra;
+                all_tabs[i] = tab;
+            }
+        }
+
         let background = self.mode_info.style.colors.text_unselected.background;
 
         self.tab_line = tab_line(
@@ -1438,6 +3735,7 @@ Examples:
             self.tabs.iter().find(|t| t.active),
             &self.mode_info,
             self.config.hide_swap_layout_indication,
+            self.config.show_position.is_some(),
             &background,
         );
 
@@ -1470,3 +3768,154 @@ Examples:
 // - State broadcast and inheritance
 // - Pipe protocol handling (status updates, list command)
 // - Tab rename confirmation loop
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pools_combine_in_order_without_repeats() {
+        let names = names_from_pools("nato+greek");
+        assert_eq!(names[0], "alpha");
+        assert_eq!(names[26], "beta");
+        assert_eq!(names.iter().filter(|n| *n == "alpha").count(), 1);
+        assert_eq!(names.iter().filter(|n| *n == "delta").count(), 1);
+        assert_eq!(names.len(), 26 + 24 - 2);
+    }
+
+    #[test]
+    fn pool_keywords_ignore_case_and_unknown_names() {
+        assert_eq!(names_from_pools(" StarWars + nope "), names_from_pools("starwars"));
+        assert!(names_from_pools("nope").is_empty());
+        assert!(names_from_pools("").is_empty());
+    }
+
+    #[test]
+    fn position_labels_are_one_based() {
+        assert_eq!(PositionFormat::Angle.label("alpha", 0), "alpha <1>");
+        assert_eq!(PositionFormat::Colon.label("alpha", 2), "3:alpha");
+        assert_eq!(PositionFormat::Superscript.label("alpha", 11), "alpha¹²");
+    }
+
+    fn pane(title: &str, command: Option<&str>) -> PaneInfo {
+        PaneInfo {
+            title: title.to_string(),
+            terminal_command: command.map(|c| c.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn command_names_come_from_the_command_first() {
+        assert_eq!(command_name(&pane("Pane #1", Some("make test"))).as_deref(), Some("make-test"));
+        assert_eq!(command_name(&pane("", Some("/usr/bin/claude api --verbose"))).as_deref(), Some("claude-api"));
+        assert_eq!(command_name(&pane("htop", Some("zsh"))), None);
+        assert_eq!(command_name(&pane("htop", None)).as_deref(), Some("htop"));
+    }
+
+    #[test]
+    fn prompt_titles_give_no_command_name() {
+        for title in ["", "Pane #12", "user@host: ~/src", "~/src/api", "/tmp"] {
+            assert!(is_prompt_title(title), "{:?}", title);
+            assert_eq!(command_name(&pane(title, None)), None);
+        }
+        assert!(!is_prompt_title("Pane #x"));
+        assert!(!is_prompt_title("cargo watch"));
+    }
+
+    #[test]
+    fn fnv1a_matches_reference_values() {
+        // Published FNV-1a 64-bit test vectors: hash mode names must not move between builds
+        assert_eq!(fnv1a(""), 0xcbf29ce484222325);
+        assert_eq!(fnv1a("a"), 0xaf63dc4c8601ec8c);
+        assert_eq!(fnv1a("foobar"), 0x85944171f73967e8);
+    }
+
+    fn config(pairs: &[(&str, &str)]) -> Config {
+        Config::from_btreemap(&pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect())
+    }
+
+    #[test]
+    fn name_template_expands_session_pool_and_counter() {
+        let mut state = State {
+            config: config(&[("names", "alpha bravo"), ("name_template", "{session}-{pool}-{n}")]),
+            ..Default::default()
+        };
+        // {session} waits for the session name
+        assert_eq!(state.expand_name_template("alpha".to_string()), ("alpha".to_string(), None));
+        state.mode_info.session_name = Some("work".to_string());
+        assert_eq!(state.expand_name_template("alpha".to_string()),
+            ("work-alpha-1".to_string(), Some("alpha".to_string())));
+        assert_eq!(state.expand_name_template("bravo".to_string()),
+            ("work-bravo-2".to_string(), Some("bravo".to_string())));
+    }
+
+    #[test]
+    fn template_pool_name_reverses_the_expansion() {
+        let templated = config(&[("names", "alpha bravo"), ("name_template", "{session}-{pool}-{n}")]);
+        assert_eq!(templated.template_pool_name("work-bravo-12", Some("work")).as_deref(), Some("bravo"));
+        assert_eq!(templated.template_pool_name("work-bravo-", Some("work")), None);
+        assert_eq!(templated.template_pool_name("work-bravo-x", Some("work")), None);
+        assert_eq!(templated.template_pool_name("play-bravo-1", Some("work")), None);
+        assert_eq!(templated.template_pool_name("work-bravo-1", None), None);
+        // A template without {pool} is ignored altogether
+        assert_eq!(config(&[("name_template", "{session}")]).name_template, None);
+    }
+
+    fn panes(statuses: &[ActivityStatus]) -> BTreeMap<u32, ActivityStatus> {
+        statuses.iter().cloned().enumerate().map(|(i, s)| (i as u32, s)).collect()
+    }
+
+    #[test]
+    fn aggregate_status_takes_the_highest_default_priority() {
+        let config = config(&[]);
+        assert_eq!(config.aggregate_status(&BTreeMap::new()), None);
+        assert_eq!(config.aggregate_status(&panes(&[ActivityStatus::Idle, ActivityStatus::Working])),
+            Some(ActivityStatus::Working));
+        assert_eq!(config.aggregate_status(&panes(&[ActivityStatus::Question, ActivityStatus::Attention])),
+            Some(ActivityStatus::Attention));
+        assert_eq!(config.aggregate_status(&panes(&[ActivityStatus::Sleeping, ActivityStatus::Unknown])),
+            Some(ActivityStatus::Sleeping));
+    }
+
+    #[test]
+    fn status_priority_outranks_defaults_in_list_order() {
+        let config = config(&[("custom_states", "testing"), ("status_priority", "working testing")]);
+        let testing = ActivityStatus::Custom("testing".to_string());
+        assert_eq!(config.aggregate_status(&panes(&[ActivityStatus::Attention, testing.clone()])),
+            Some(testing.clone()));
+        assert_eq!(config.aggregate_status(&panes(&[testing, ActivityStatus::Working])),
+            Some(ActivityStatus::Working));
+    }
+
+    #[test]
+    fn progress_styles() {
+        assert_eq!(ProgressStyle::Percent.format(42), "42%");
+        assert_eq!(ProgressStyle::Bar.format(0), "▱▱▱▱▱");
+        assert_eq!(ProgressStyle::Bar.format(42), "▰▰▱▱▱");
+        assert_eq!(ProgressStyle::Bar.format(50), "▰▰▰▱▱");
+        assert_eq!(ProgressStyle::Bar.format(100), "▰▰▰▰▰");
+    }
+
+    fn viewport(lines: &[&str]) -> Vec<String> {
+        lines.iter().map(|line| line.to_string()).collect()
+    }
+
+    #[test]
+    fn detect_status_sees_only_the_last_non_blank_lines() {
+        let config = config(&[("detect_question", "proceed\\?"), ("detect_lines", "2")]);
+        let asking = viewport(&["Do you want to proceed?", "> yes", "  no", "", "   "]);
+        assert_eq!(config.detect_status(&asking), None);
+        let asking = viewport(&["Do you want to proceed?", "> yes", "", "   "]);
+        assert_eq!(config.detect_status(&asking), Some(ActivityStatus::Question));
+        assert_eq!(config.detect_status(&viewport(&[])), None);
+    }
+
+    #[test]
+    fn detect_status_anchors_per_line_and_prefers_priority() {
+        let config = config(&[("detect_idle", "[$#]$"), ("detect_attention", "(?i)error:")]);
+        assert_eq!(config.detect_status(&viewport(&["~/src $ ls", "running"])), None);
+        assert_eq!(config.detect_status(&viewport(&["running", "~/src $ "])), Some(ActivityStatus::Idle));
+        assert_eq!(config.detect_status(&viewport(&["ERROR: boom", "~/src $ "])), Some(ActivityStatus::Attention));
+    }
+}